@@ -0,0 +1,321 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use crate::entity::Entity;
+use crate::map::GameMap;
+use crate::{Player, PlayerState, BLOCKSIZE, cast_ray};
+
+/// Enemies further than this don't notice the player
+pub const SIGHT_DISTANCE: f32 = BLOCKSIZE as f32 * 10.0;
+/// Degrees to both sides of where an idle enemy faces that it sees
+pub const SIGHT_ANGLE: f32 = 60.0;
+/// Enemies closer than this to the player they see stop and shoot
+pub const ATTACK_RANGE: f32 = BLOCKSIZE as f32 * 6.0;
+/// Seconds between the shots of an enemy
+pub const ATTACK_COOLDOWN: f32 = 1.0;
+/// Health a shot of an enemy takes from the player, enemy shots always hit
+pub const ENEMY_SHOT_DAMAGE: i32 = 10;
+/// Enemies shoot when they face the player closer than this, in degrees
+const AIM_ANGLE: f32 = 10.0;
+const ENEMY_SPEED: f32 = 120.0; // Units per second
+const ENEMY_TURN_SPEED: f32 = 180.0; // Degrees per second
+/// Enemies chasing the player closer than this to where they saw them last have arrived there
+const ARRIVE_DISTANCE: f32 = 4.0;
+
+/// What an enemy is doing
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AiState{
+    Idle, // stands and looks to where it faces
+    Chase{ target_x: f32, target_y: f32 }, // goes to where it saw the player last
+    Attack, // stands, turns to the player and shoots
+}
+
+/// Returns the angle from the first point to the second, like Player::angle
+pub fn angle_to(from_x: f32, from_y: f32, to_x: f32, to_y: f32) -> f32{
+    // Y grows downwards on the map, angles grow counterclockwise
+    (from_y - to_y).atan2(to_x - from_x).to_degrees().rem_euclid(360.0)
+}
+
+/// Returns the signed difference from the first angle to the second, between -180 and 180
+fn angle_difference(from: f32, to: f32) -> f32{
+    (to - from + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Returns true if no first level wall is between the points, the ray of cast_ray is used like the rays of the screen
+pub fn can_see(game_map: &GameMap, from_x: f32, from_y: f32, to_x: f32, to_y: f32) -> bool{
+    let distance = ((to_x - from_x).powi(2) + (to_y - from_y).powi(2)).sqrt();
+    let wall = cast_ray(game_map, from_x, from_y, angle_to(from_x, from_y, to_x, to_y))[0];
+    wall.hit_side == -1 || wall.distance >= distance
+}
+
+/// Returns true if the enemy sees the living player, idle enemies only see in front of them
+pub fn sees_player(game_map: &GameMap, enemy: &Entity, player: &Player) -> bool{
+    if player.state != PlayerState::Alive {return false;}
+    let distance = enemy.distance_to(player.pos_x, player.pos_y);
+    if distance > SIGHT_DISTANCE {return false;}
+    let angle = angle_to(enemy.pos_x, enemy.pos_y, player.pos_x, player.pos_y);
+    if enemy.state == AiState::Idle && angle_difference(enemy.angle, angle).abs() > SIGHT_ANGLE {return false;}
+    can_see(game_map, enemy.pos_x, enemy.pos_y, player.pos_x, player.pos_y)
+}
+
+/// Returns the cells to walk through from the start to the goal over the empty cells of the first level, without the start.
+/// Cells can be left diagonally if both cells next to the corner are empty. None if the goal can't be reached
+pub fn find_path(game_map: &GameMap, start: (usize, usize), goal: (usize, usize)) -> Option<Vec<(usize, usize)>>{
+    let empty = |(map_x, map_y): (i32, i32)| map_x >= 0 && map_y >= 0 && (map_x as usize) < game_map.width && (map_y as usize) < game_map.height
+        && game_map.get_cell(0, map_x as usize, map_y as usize) == 0;
+    if !empty((goal.0 as i32, goal.1 as i32)) {return None;}
+    // Straight steps cost 10 and diagonal ones 14, integers keep the heap ordered
+    let heuristic = |(map_x, map_y): (usize, usize)| {
+        let (dx, dy) = (map_x.abs_diff(goal.0), map_y.abs_diff(goal.1));
+        10 * dx.max(dy) + 4 * dx.min(dy)
+    };
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), start))]);
+    let mut costs: HashMap<(usize, usize), usize> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    while let Some(Reverse((_, cell))) = open.pop(){
+        if cell == goal{
+            let mut path = vec![goal];
+            while let Some(previous) = came_from.get(path.last().unwrap()).filter(|previous| **previous != start){
+                path.push(*previous);
+            }
+            if goal == start {path.clear();}
+            path.reverse();
+            return Some(path);
+        }
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)]{
+            let next = (cell.0 as i32 + dx, cell.1 as i32 + dy);
+            if !empty(next) {continue;}
+            let diagonal = dx != 0 && dy != 0;
+            if diagonal && !(empty((cell.0 as i32 + dx, cell.1 as i32)) && empty((cell.0 as i32, cell.1 as i32 + dy))) {continue;}
+            let next = (next.0 as usize, next.1 as usize);
+            let cost = costs[&cell] + if diagonal {14} else {10};
+            if costs.get(&next).is_some_and(|known| *known <= cost) {continue;}
+            costs.insert(next, cost);
+            came_from.insert(next, cell);
+            open.push(Reverse((cost + heuristic(next), next)));
+        }
+    }
+    None
+}
+
+/// Turns the enemy towards the angle, at most ENEMY_TURN_SPEED for delta_time seconds
+fn turn_towards(enemy: &mut Entity, angle: f32, delta_time: f32){
+    let step = ENEMY_TURN_SPEED * delta_time;
+    enemy.angle = (enemy.angle + angle_difference(enemy.angle, angle).clamp(-step, step)).rem_euclid(360.0);
+}
+
+/// Runs an enemy for a tick of delta_time seconds, returns true if it shot at the player
+pub fn update_enemy(game_map: &GameMap, player: &Player, player_radius: f32, enemy: &mut Entity, delta_time: f32) -> bool{
+    enemy.cooldown = (enemy.cooldown - delta_time).max(0.0);
+    let player_angle = angle_to(enemy.pos_x, enemy.pos_y, player.pos_x, player.pos_y);
+    if sees_player(game_map, enemy, player){
+        enemy.last_seen = Some((player.pos_x, player.pos_y));
+        enemy.state = if enemy.distance_to(player.pos_x, player.pos_y) <= ATTACK_RANGE {AiState::Attack}
+            else {AiState::Chase{ target_x: player.pos_x, target_y: player.pos_y }};
+    }
+    // Losing the player goes to where they were last seen, not to where they are now
+    else if enemy.state == AiState::Attack{
+        let (target_x, target_y) = enemy.last_seen.unwrap_or((enemy.pos_x, enemy.pos_y));
+        enemy.state = AiState::Chase{ target_x, target_y };
+    }
+    // A path is only kept while chasing
+    if !matches!(enemy.state, AiState::Chase{ .. }){
+        enemy.path.clear();
+        enemy.path_goal = None;
+    }
+    match enemy.state{
+        AiState::Idle => false,
+        AiState::Attack => {
+            turn_towards(enemy, player_angle, delta_time);
+            if enemy.cooldown > 0.0 || angle_difference(enemy.angle, player_angle).abs() > AIM_ANGLE {return false;}
+            enemy.cooldown = ATTACK_COOLDOWN;
+            true
+        },
+        AiState::Chase{ target_x, target_y } => {
+            if enemy.distance_to(target_x, target_y) <= ARRIVE_DISTANCE{
+                enemy.state = AiState::Idle;
+                enemy.path.clear();
+                enemy.path_goal = None;
+                return false;
+            }
+            let block = BLOCKSIZE as f32;
+            let cell = |pos_x: f32, pos_y: f32| ((pos_x / block) as usize, (pos_y / block) as usize);
+            let goal = cell(target_x, target_y);
+            if enemy.path_goal != Some(goal){
+                let Some(path) = find_path(game_map, cell(enemy.pos_x, enemy.pos_y), goal) else {
+                    enemy.state = AiState::Idle;
+                    enemy.path.clear();
+                    enemy.path_goal = None;
+                    return false;
+                };
+                enemy.path = path;
+                enemy.path_goal = Some(goal);
+            }
+            // The cell it walked into is left behind
+            if enemy.path.first() == Some(&cell(enemy.pos_x, enemy.pos_y)) {enemy.path.remove(0);}
+            // Walks through the centers of the cells, then straight to the target in its cell
+            let (next_x, next_y) = match enemy.path.first(){
+                Some((map_x, map_y)) => ((*map_x as f32 + 0.5) * block, (*map_y as f32 + 0.5) * block),
+                None => (target_x, target_y),
+            };
+            let angle = angle_to(enemy.pos_x, enemy.pos_y, next_x, next_y);
+            turn_towards(enemy, angle, delta_time);
+            let step = (ENEMY_SPEED * delta_time).min(enemy.distance_to(next_x, next_y));
+            let (dir_x, dir_y) = crate::get_deltas(angle);
+            let (pos_x, pos_y) = (enemy.pos_x + dir_x * step, enemy.pos_y + dir_y * step);
+            // Enemies don't walk into the player
+            let player_distance = ((player.pos_x - pos_x).powi(2) + (player.pos_y - pos_y).powi(2)).sqrt();
+            if player_distance >= enemy.radius + player_radius || player_distance > enemy.distance_to(player.pos_x, player.pos_y){
+                enemy.pos_x = pos_x;
+                enemy.pos_y = pos_y;
+            }
+            false
+        },
+    }
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::Behaviour;
+
+    /// An empty room of 8x8 cells with a wall across the middle that has a gap at the bottom
+    fn test_map() -> GameMap{
+        let mut game_map = GameMap::new(8, 8);
+        for map_y in 0..7{
+            game_map.first_level[map_y][4] = 1;
+        }
+        game_map
+    }
+
+    fn center(map_x: usize, map_y: usize) -> (f32, f32){
+        ((map_x as f32 + 0.5) * BLOCKSIZE as f32, (map_y as f32 + 0.5) * BLOCKSIZE as f32)
+    }
+
+    #[test]
+    fn test_angle_to() {
+        assert_eq!(angle_to(0.0, 0.0, 10.0, 0.0), 0.0);
+        // Y grows downwards, so up on the map is 90 degrees
+        assert_eq!(angle_to(0.0, 0.0, 0.0, -10.0), 90.0);
+        assert_eq!(angle_to(0.0, 0.0, 0.0, 10.0), 270.0);
+        assert_eq!(angle_difference(350.0, 10.0), 20.0);
+        assert_eq!(angle_difference(10.0, 350.0), -20.0);
+    }
+
+    #[test]
+    fn test_can_see() {
+        let game_map = test_map();
+        let (from, behind_wall, below_wall) = (center(1, 1), center(6, 1), center(6, 7));
+        assert!(!can_see(&game_map, from.0, from.1, behind_wall.0, behind_wall.1));
+        assert!(can_see(&game_map, center(1, 7).0, center(1, 7).1, below_wall.0, below_wall.1));
+        assert!(can_see(&game_map, from.0, from.1, center(3, 5).0, center(3, 5).1));
+    }
+
+    #[test]
+    fn test_sees_player() {
+        let game_map = test_map();
+        let (pos_x, pos_y) = center(1, 1);
+        let mut enemy = Entity::new(Behaviour::Enemy, pos_x, pos_y, 0.0, 0);
+        let player = Player::new(center(3, 1).0, pos_y, 0.0);
+        assert!(sees_player(&game_map, &enemy, &player));
+        // Idle enemies don't see behind them, chasing ones look around
+        enemy.angle = 180.0;
+        assert!(!sees_player(&game_map, &enemy, &player));
+        enemy.state = AiState::Attack;
+        assert!(sees_player(&game_map, &enemy, &player));
+        // Behind the wall the player is hidden
+        let hidden = Player::new(center(6, 1).0, pos_y, 0.0);
+        assert!(!sees_player(&game_map, &enemy, &hidden));
+    }
+
+    #[test]
+    fn test_find_path() {
+        let game_map = test_map();
+        // Around the bottom of the wall
+        let path = find_path(&game_map, (1, 1), (6, 1)).unwrap();
+        assert_eq!(path.last(), Some(&(6, 1)));
+        assert!(path.contains(&(4, 7)));
+        assert!(path.iter().all(|(map_x, map_y)| game_map.get_cell(0, *map_x, *map_y) == 0));
+        // Every step goes to a neighbour
+        for pair in path.windows(2){
+            assert!(pair[0].0.abs_diff(pair[1].0) <= 1 && pair[0].1.abs_diff(pair[1].1) <= 1);
+        }
+        // 6 steps down, 2 through the gap without cutting its corners and 6 steps up
+        assert_eq!(path.len(), 14);
+        assert_eq!(find_path(&game_map, (2, 2), (2, 2)), Some(vec![]));
+        assert_eq!(find_path(&game_map, (2, 2), (4, 2)), None);
+        // Corners can't be cut
+        let mut corner = GameMap::new(2, 2);
+        corner.first_level[0][1] = 1;
+        assert_eq!(find_path(&corner, (0, 0), (1, 1)), Some(vec![(0, 1), (1, 1)]));
+        corner.first_level[1][0] = 1;
+        assert_eq!(find_path(&corner, (0, 0), (1, 1)), None);
+    }
+
+    #[test]
+    fn test_update_enemy_attack() {
+        let game_map = test_map();
+        let (pos_x, pos_y) = center(1, 5);
+        let mut enemy = Entity::new(Behaviour::Enemy, pos_x, pos_y, 30.0, 0);
+        let player = Player::new(center(3, 3).0, center(3, 3).1, 0.0);
+        // It turns to the player first, then shoots every ATTACK_COOLDOWN seconds
+        let shots: Vec<usize> = (0..120).filter(|_| update_enemy(&game_map, &player, 16.0, &mut enemy, 1.0 / 60.0)).collect();
+        assert_eq!(enemy.state, AiState::Attack);
+        assert_eq!(shots.len(), 2);
+        assert!(shots[0] < 10 && shots[1] - shots[0] >= 60);
+        assert_eq!((enemy.pos_x, enemy.pos_y), (pos_x, pos_y));
+    }
+
+    #[test]
+    fn test_update_enemy_chase() {
+        let game_map = test_map();
+        let (pos_x, pos_y) = center(1, 1);
+        let mut enemy = Entity::new(Behaviour::Enemy, pos_x, pos_y, 0.0, 0);
+        // The player was seen behind the wall, out of the attack range
+        let player = Player::new(center(6, 1).0, center(6, 1).1, 0.0);
+        enemy.state = AiState::Chase{ target_x: player.pos_x, target_y: player.pos_y };
+        // The path is found once and walked cell by cell
+        update_enemy(&game_map, &player, 16.0, &mut enemy, 1.0 / 60.0);
+        assert_eq!((enemy.path.len(), enemy.path_goal), (14, Some((6, 1))));
+        for _ in 0..600{
+            update_enemy(&game_map, &player, 16.0, &mut enemy, 1.0 / 60.0);
+            assert!(enemy.path.len() <= 14);
+            let cell = ((enemy.pos_x / BLOCKSIZE as f32) as usize, (enemy.pos_y / BLOCKSIZE as f32) as usize);
+            assert_eq!(game_map.get_cell(0, cell.0, cell.1), 0);
+            if enemy.state == AiState::Attack {break;}
+        }
+        // Around the wall it sees the player again
+        assert_eq!(enemy.state, AiState::Attack);
+        assert!(enemy.pos_x > 4.0 * BLOCKSIZE as f32);
+    }
+
+    #[test]
+    fn test_update_enemy_lose_player() {
+        let game_map = test_map();
+        let (pos_x, pos_y) = center(1, 1);
+        let mut enemy = Entity::new(Behaviour::Enemy, pos_x, pos_y, 0.0, 0);
+        enemy.state = AiState::Chase{ target_x: center(2, 1).0, target_y: pos_y };
+        // The player is hidden, so it walks to where it saw them and stops there
+        let player = Player::new(center(6, 1).0, pos_y, 0.0);
+        for _ in 0..120{
+            update_enemy(&game_map, &player, 16.0, &mut enemy, 1.0 / 60.0);
+        }
+        assert_eq!(enemy.state, AiState::Idle);
+        assert!((enemy.pos_x - center(2, 1).0).abs() <= ARRIVE_DISTANCE);
+    }
+
+    #[test]
+    fn test_update_enemy_last_seen() {
+        let game_map = test_map();
+        let mut enemy = Entity::new(Behaviour::Enemy, center(1, 5).0, center(1, 5).1, 30.0, 0);
+        let seen = Player::new(center(3, 3).0, center(3, 3).1, 0.0);
+        update_enemy(&game_map, &seen, 16.0, &mut enemy, 1.0 / 60.0);
+        assert_eq!(enemy.state, AiState::Attack);
+        // The player went behind the wall, the enemy goes to where it saw them and not through the wall to them
+        let hidden = Player::new(center(6, 1).0, center(6, 1).1, 0.0);
+        update_enemy(&game_map, &hidden, 16.0, &mut enemy, 1.0 / 60.0);
+        assert_eq!(enemy.state, AiState::Chase{ target_x: seen.pos_x, target_y: seen.pos_y });
+        assert_eq!(enemy.path_goal, Some((3, 3)));
+    }
+}
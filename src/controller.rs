@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+use sdl2::controller::{Axis, GameController};
+use sdl2::GameControllerSubsystem;
+use crate::Movement;
+use crate::input::{Binding, CONTROLLER_BUTTONS, TRIGGERS};
+
+/// Part of a stick's travel around the center that is ignored, sticks don't rest exactly at the center
+pub const DEAD_ZONE: f32 = 0.2;
+/// Part of a trigger's travel after which it counts as pressed
+pub const TRIGGER_THRESHOLD: f32 = 0.5;
+const AXIS_MAX: f32 = i16::MAX as f32;
+
+/// Returns the position of a stick between -1 and 1 on both axes.
+/// It is 0 inside the dead zone and starts from 0 at its edge, so small motions can still be made
+pub fn stick(x: i16, y: i16) -> (f32, f32){
+    // i16::MIN is one further than i16::MAX
+    let (x, y) = ((x as f32 / AXIS_MAX).max(-1.0), (y as f32 / AXIS_MAX).max(-1.0));
+    let length = (x * x + y * y).sqrt();
+    if length <= DEAD_ZONE {return (0.0, 0.0);}
+    // The zone is round, so diagonals don't snap to the axes
+    let scale = ((length - DEAD_ZONE) / (1.0 - DEAD_ZONE)).min(1.0) / length;
+    (x * scale, y * scale)
+}
+
+/// Returns true if the trigger at the value counts as a pressed button
+pub fn trigger_pressed(value: i16) -> bool{
+    value as f32 / AXIS_MAX > TRIGGER_THRESHOLD
+}
+
+/// Returns the movement of the sticks(left moves and strafes, right turns and looks up and down) at the positions SDL reports
+pub fn stick_movement(left: (i16, i16), right: (i16, i16)) -> Movement{
+    let (strafe, backward) = stick(left.0, left.1);
+    let (turn_right, look_down) = stick(right.0, right.1);
+    // Stick Y grows downwards like the screen
+    Movement{ forward: -backward, strafe, turn: -turn_right, pitch: -look_down, ..Movement::default() }
+}
+
+/// Connected game controllers, they can be plugged in and out while playing
+pub struct Controllers{
+    subsystem: GameControllerSubsystem,
+    connected: Vec<GameController>,
+    pressed_triggers: HashSet<(u32, Axis)>, // joystick id of the controller and the trigger
+}
+
+impl Controllers{
+    /// Returns no controllers, SDL sends ControllerDeviceAdded for the ones already connected
+    pub fn new(subsystem: GameControllerSubsystem) -> Controllers{
+        Controllers{ subsystem, connected: Vec::new(), pressed_triggers: HashSet::new() }
+    }
+
+    /// Opens the controller at the joystick index of a ControllerDeviceAdded event, returns its name
+    pub fn add(&mut self, joystick_index: u32) -> Result<String, String>{
+        let controller = self.subsystem.open(joystick_index).map_err(|err| format!("Couldn't open controller {}: {}", joystick_index, err))?;
+        let name = controller.name();
+        if self.connected.iter().all(|connected| connected.instance_id() != controller.instance_id()){
+            self.connected.push(controller);
+        }
+        Ok(name)
+    }
+
+    /// Closes the controller with the joystick id of a ControllerDeviceRemoved event
+    pub fn remove(&mut self, id: u32){
+        self.connected.retain(|controller| controller.instance_id() != id);
+        self.pressed_triggers.retain(|(trigger_id, _)| *trigger_id != id);
+    }
+
+    /// Returns the binding of the trigger of a ControllerAxisMotion event if the motion pressed it
+    pub fn trigger_motion(&mut self, id: u32, axis: Axis, value: i16) -> Option<Binding>{
+        if !TRIGGERS.contains(&axis) {return None;}
+        if !trigger_pressed(value){
+            self.pressed_triggers.remove(&(id, axis));
+            return None;
+        }
+        // Only the motion past the threshold presses it, not the ones after it
+        self.pressed_triggers.insert((id, axis)).then_some(Binding::Trigger(axis))
+    }
+
+    /// Returns the held buttons and triggers of every controller
+    pub fn held(&self) -> Vec<Binding>{
+        let mut held: Vec<Binding> = Vec::new();
+        for controller in &self.connected{
+            held.extend(CONTROLLER_BUTTONS.iter().filter(|button| controller.button(**button)).map(|button| Binding::Controller(*button)));
+            held.extend(TRIGGERS.iter().filter(|trigger| trigger_pressed(controller.axis(**trigger))).map(|trigger| Binding::Trigger(*trigger)));
+        }
+        held
+    }
+
+    /// Returns the movement of the sticks of every controller
+    pub fn movement(&self) -> Movement{
+        self.connected.iter().fold(Movement::default(), |movement, controller| movement.combine(stick_movement(
+            (controller.axis(Axis::LeftX), controller.axis(Axis::LeftY)),
+            (controller.axis(Axis::RightX), controller.axis(Axis::RightY)),
+        )))
+    }
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stick() {
+        assert_eq!(stick(0, 0), (0.0, 0.0));
+        // Resting a little off the center is ignored
+        assert_eq!(stick(3000, -3000), (0.0, 0.0));
+        // Just outside the dead zone is a small motion
+        let (x, y) = stick((AXIS_MAX * 0.25) as i16, 0);
+        assert!((x - 0.0625).abs() < 0.001 && y == 0.0);
+        assert_eq!(stick(i16::MAX, 0), (1.0, 0.0));
+        assert_eq!(stick(0, i16::MIN), (0.0, -1.0));
+        // Pushed fully into a corner the stick isn't longer than 1
+        let (x, y) = stick(i16::MAX, i16::MAX);
+        assert!((x * x + y * y - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_stick_movement() {
+        let movement = stick_movement((0, i16::MIN), (i16::MAX, 0));
+        assert_eq!(movement, Movement{ forward: 1.0, turn: -1.0, ..Movement::default() });
+        let movement = stick_movement((i16::MAX, 0), (0, i16::MAX));
+        assert_eq!(movement, Movement{ strafe: 1.0, pitch: -1.0, ..Movement::default() });
+    }
+
+    #[test]
+    fn test_trigger_pressed() {
+        assert!(!trigger_pressed(0));
+        assert!(!trigger_pressed(i16::MAX / 2));
+        assert!(trigger_pressed(i16::MAX / 2 + 10));
+        assert!(trigger_pressed(i16::MAX));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Game, GameEvent, PlayerState, BLOCKSIZE};
+
+/// Health the player spawns with
+pub const PLAYER_HEALTH: i32 = 100;
+/// Most armour the player can have
+pub const MAX_ARMOUR: i32 = 100;
+/// Seconds between the hurts of a hazard the player stands in
+pub const HAZARD_INTERVAL: f32 = 0.5;
+
+/// Health of the player and of the entities that can be hurt, everything takes damage through Health::take
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Health{
+    pub health: i32, // dead at 0 or less
+    pub armour: i32, // absorbs half of the damage until it runs out
+}
+
+impl Health{
+    pub fn new(health: i32, armour: i32) -> Health{
+        Health{ health, armour }
+    }
+
+    /// Takes the damage, armour absorbs half of it(rounded down) while it lasts. Returns the health lost
+    pub fn take(&mut self, amount: i32) -> i32{
+        let absorbed = (amount / 2).min(self.armour);
+        self.armour -= absorbed;
+        self.health -= amount - absorbed;
+        amount - absorbed
+    }
+
+    pub fn is_dead(&self) -> bool{
+        self.health <= 0
+    }
+}
+
+/// Cell of the map whose floor hurts the player standing on it, like lava or acid
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hazard{
+    pub map_x: usize,
+    pub map_y: usize,
+    pub damage: i32, // taken every HAZARD_INTERVAL
+}
+
+/// Hurts the player, source is where the damage came from if it came from somewhere.
+/// Returns Hurt, or Died when the health runs out, dead players can't be hurt
+pub fn damage_player(game: &mut Game, amount: i32, source: Option<(f32, f32)>) -> Option<GameEvent>{
+    if game.player.state != PlayerState::Alive {return None;}
+    let lost = game.player.health.take(amount);
+    if !game.player.health.is_dead() {return Some(GameEvent::Hurt(lost));}
+    game.player.state = PlayerState::Dead{ time: 0.0, killer: source };
+    Some(GameEvent::Died)
+}
+
+/// Hurts the player standing on the floor of hazards, once every HAZARD_INTERVAL for a tick of delta_time seconds
+pub fn update_hazards(game: &mut Game, delta_time: f32) -> Option<GameEvent>{
+    game.player.hazard_cooldown = (game.player.hazard_cooldown - delta_time).max(0.0);
+    let player = &game.player;
+    if player.pos_z > 0.0 || player.hazard_cooldown > 0.0 {return None;}
+    let cell = ((player.pos_x / BLOCKSIZE as f32) as usize, (player.pos_y / BLOCKSIZE as f32) as usize);
+    let damage: i32 = game.hazards.iter().filter(|hazard| (hazard.map_x, hazard.map_y) == cell).map(|hazard| hazard.damage).sum();
+    if damage == 0 {return None;}
+    // Stepping out and back in doesn't hurt sooner
+    game.player.hazard_cooldown = HAZARD_INTERVAL;
+    damage_player(game, damage, None)
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn test_take() {
+        let mut health = Health::new(100, 15);
+        // Half of the damage goes to the armour
+        assert_eq!(health.take(20), 10);
+        assert_eq!(health, Health::new(90, 5));
+        // Only as much as is left of it
+        assert_eq!(health.take(20), 15);
+        assert_eq!(health, Health::new(75, 0));
+        assert_eq!(health.take(75), 75);
+        assert!(health.is_dead());
+    }
+
+    #[test]
+    fn test_damage_player() {
+        let mut game = Game::new(Player::new(96.0, 96.0, 0.0), map::GameMap::new(4, 4));
+        assert_eq!(damage_player(&mut game, 30, None), Some(GameEvent::Hurt(30)));
+        assert_eq!(game.player.health.health, PLAYER_HEALTH - 30);
+        assert_eq!(damage_player(&mut game, PLAYER_HEALTH, Some((200.0, 96.0))), Some(GameEvent::Died));
+        assert_eq!(game.player.state, PlayerState::Dead{ time: 0.0, killer: Some((200.0, 96.0)) });
+        assert_eq!(damage_player(&mut game, 10, None), None);
+    }
+
+    #[test]
+    fn test_update_hazards() {
+        let mut game = Game::new(Player::new(96.0, 96.0, 0.0), map::GameMap::new(4, 4));
+        game.hazards = vec![Hazard{ map_x: 1, map_y: 1, damage: 10 }];
+        // Standing in the hazard hurts right away, then every HAZARD_INTERVAL
+        let hurts = (0..TICK_RATE).filter_map(|_| update_hazards(&mut game, TICK_TIME)).count();
+        assert_eq!(hurts, 2);
+        assert_eq!(game.player.health.health, PLAYER_HEALTH - 20);
+        // Jumping over it or standing next to it doesn't
+        game.player.hazard_cooldown = 0.0;
+        game.player.pos_z = 10.0;
+        assert_eq!(update_hazards(&mut game, TICK_TIME), None);
+        game.player.pos_z = 0.0;
+        game.player.pos_x = 32.0;
+        assert_eq!(update_hazards(&mut game, TICK_TIME), None);
+    }
+}
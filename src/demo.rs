@@ -0,0 +1,220 @@
+use std::fmt;
+use std::fs;
+use crate::{Game, GameEvent, Input, Movement, TICK_RATE, TICK_TIME, update};
+
+/// Flags of the input lines after the numbers
+const INPUT_USAGE: &str = "input <ticks> <forward> <strafe> <turn> <pitch> <mouse turn> <mouse look> [jump] [crouch] [fire] [reload] [next|previous]... [select <slot>]";
+
+/// Inputs of every tick of a play session, replaying them from the start of the map gives the same game
+#[derive(Debug, Clone, PartialEq)]
+pub struct Demo{
+    pub map: String, // path of the map the session was played on
+    pub weapons: Option<String>, // path of the weapons file the session was played with, None played with Arsenal::default
+    pub inputs: Vec<Input>, // one for every tick
+}
+
+impl Demo{
+    /// Returns an empty demo of the map and the weapons file
+    pub fn new(map: &str, weapons: Option<&str>) -> Demo{
+        Demo{ map: map.to_string(), weapons: weapons.map(str::to_string), inputs: Vec::new() }
+    }
+
+    /// Adds the input of a tick
+    pub fn record(&mut self, input: &Input){
+        self.inputs.push(*input);
+    }
+
+    /// Writes the demo to a file
+    pub fn save(&self, path: &str) -> Result<(), String>{
+        fs::write(path, self.to_string()).map_err(|err| format!("Couldn't write {}: {}", path, err))
+    }
+}
+
+/// Updates the game with every input of the demo, returns the events of every tick
+pub fn play(game: &mut Game, demo: &Demo) -> Vec<GameEvent>{
+    demo.inputs.iter().flat_map(|input| update(game, input, TICK_TIME)).collect()
+}
+
+/// Writes the demo in the format parse_demo reads, ticks with the same input are written once
+impl fmt::Display for Demo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# {}", INPUT_USAGE)?;
+        writeln!(f, "tick_rate {}", TICK_RATE)?;
+        writeln!(f, "map {}", self.map)?;
+        if let Some(weapons) = &self.weapons {writeln!(f, "weapons {}", weapons)?;}
+        let mut idx = 0;
+        while idx < self.inputs.len(){
+            let input = self.inputs[idx];
+            let ticks = self.inputs[idx..].iter().take_while(|other| **other == input).count();
+            let movement = input.movement;
+            // f32 is written with as many digits as it needs to be read back exactly
+            write!(f, "input {} {} {} {} {} {} {}", ticks, movement.forward, movement.strafe, movement.turn, movement.pitch, input.turn, input.look)?;
+            for (name, held) in [("jump", movement.jump), ("crouch", movement.crouch), ("fire", input.fire), ("reload", input.reload)]{
+                if held {write!(f, " {}", name)?;}
+            }
+            let cycle = if input.cycle > 0 {" next"} else {" previous"};
+            write!(f, "{}", cycle.repeat(input.cycle.unsigned_abs() as usize))?;
+            // Slots are numbered like the number keys
+            if let Some(idx) = input.select {write!(f, " select {}", idx + 1)?;}
+            writeln!(f)?;
+            idx += ticks;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a demo file, errors start with the path of the file
+pub fn load_demo(path: &str) -> Result<Demo, String>{
+    let source = fs::read_to_string(path).map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+    parse_demo(&source).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Parses the text of a demo file, errors about a single line start with its number
+pub fn parse_demo(source: &str) -> Result<Demo, String>{
+    let mut map: Option<String> = None;
+    let mut weapons: Option<String> = None;
+    let mut inputs: Vec<Input> = Vec::new();
+    for (line_idx, line) in source.lines().enumerate(){
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {continue;}
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let error = |message: String| format!("line {}: {}", line_idx + 1, message);
+        match words[0]{
+            // Ticks of another rate would move the player differently
+            "tick_rate" => {
+                if words[1..] != [TICK_RATE.to_string().as_str()]{
+                    return Err(error(format!("demo was recorded at {} ticks per second, the game runs at {}", words[1..].join(" "), TICK_RATE)));
+                }
+            },
+            "map" => match words[1..]{
+                [path] => map = Some(path.to_string()),
+                _ => return Err(error("expected 'map <path>'".to_string())),
+            },
+            "weapons" => match words[1..]{
+                [path] => weapons = Some(path.to_string()),
+                _ => return Err(error("expected 'weapons <path>'".to_string())),
+            },
+            "input" => {
+                let usage = || error(format!("expected '{}'", INPUT_USAGE));
+                let ticks = words.get(1).and_then(|word| word.parse::<usize>().ok()).ok_or_else(usage)?;
+                let numbers = words.get(2..8).ok_or_else(usage)?.iter().map(|word| word.parse::<f32>().ok()).collect::<Option<Vec<f32>>>().ok_or_else(usage)?;
+                let mut input = Input{
+                    movement: Movement{ forward: numbers[0], strafe: numbers[1], turn: numbers[2], pitch: numbers[3], ..Movement::default() },
+                    turn: numbers[4],
+                    look: numbers[5],
+                    ..Input::default()
+                };
+                let mut flags = words[8..].iter();
+                while let Some(flag) = flags.next(){
+                    match *flag{
+                        "jump" => input.movement.jump = true,
+                        "crouch" => input.movement.crouch = true,
+                        "fire" => input.fire = true,
+                        "reload" => input.reload = true,
+                        "next" => input.cycle += 1,
+                        "previous" => input.cycle -= 1,
+                        "select" => {
+                            let slot = flags.next().and_then(|word| word.parse::<usize>().ok()).filter(|slot| *slot > 0).ok_or_else(usage)?;
+                            input.select = Some(slot - 1);
+                        },
+                        _ => return Err(error(format!("unknown flag '{}', expected jump, crouch, fire, reload, next, previous or select", flag))),
+                    }
+                }
+                inputs.extend(std::iter::repeat_n(input, ticks));
+            },
+            _ => return Err(error(format!("unknown keyword '{}'", words[0]))),
+        }
+    }
+    let map = map.ok_or("map of the demo is missing")?;
+    Ok(Demo{ map, weapons, inputs })
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use crate::weapon::{self, DEFAULT_WEAPONS};
+
+    /// Inputs of a short session, walking and turning around the spawn of the default map, jumping, shooting and switching weapons
+    fn session() -> Demo{
+        let mut demo = Demo::new(map::DEFAULT_MAP, Some(DEFAULT_WEAPONS));
+        for tick in 0..240{
+            let movement = Movement{ forward: 1.0, strafe: (tick / 60 % 2) as f32 * 0.37, turn: 0.3, jump: tick == 100, ..Movement::default() };
+            demo.record(&Input{
+                movement,
+                turn: (tick % 7) as f32 * 0.15,
+                look: -0.1,
+                fire: tick % 50 < 20,
+                reload: tick == 150,
+                select: (tick == 120).then_some(1),
+                cycle: if tick == 200 {-2} else {0},
+            });
+        }
+        demo
+    }
+
+    /// Returns the game at the start of the map the demo was recorded on, with its weapons
+    fn start(demo: &Demo) -> Game{
+        let mut game = map::load_map(&demo.map).unwrap().new_game();
+        game.arm(demo.weapons.as_deref().map(|path| weapon::load_weapons(path).unwrap()).unwrap_or_default());
+        game
+    }
+
+    #[test]
+    fn test_demo_text() {
+        let demo = session();
+        let text = demo.to_string();
+        assert_eq!(parse_demo(&text), Ok(demo));
+        // Demos recorded before there were weapons files were played with the built-in gun, and are written without one
+        let old = parse_demo("map a.map").unwrap();
+        assert_eq!(old.weapons, None);
+        assert!(!old.to_string().contains("weapons"));
+        // Ticks with the same input are one line
+        let mut still = Demo::new(map::DEFAULT_MAP, None);
+        for _ in 0..120{
+            still.record(&Input::default());
+        }
+        assert_eq!(still.to_string().lines().filter(|line| line.starts_with("input")).count(), 1);
+    }
+
+    #[test]
+    fn test_replay() {
+        // Replaying the demo gives the same trajectory and shots as the session
+        let demo = session();
+        let mut game = start(&demo);
+        let mut trajectory = Vec::new();
+        let mut events = Vec::new();
+        for input in &demo.inputs{
+            events.extend(update(&mut game, input, TICK_TIME));
+            trajectory.push(game.player);
+        }
+        let mut replayed = start(&demo);
+        let replayed_demo = parse_demo(&demo.to_string()).unwrap();
+        for (idx, input) in replayed_demo.inputs.iter().enumerate(){
+            update(&mut replayed, input, TICK_TIME);
+            assert_eq!(replayed.player, trajectory[idx], "tick {}", idx);
+        }
+        let mut played = start(&demo);
+        assert_eq!(play(&mut played, &replayed_demo), events);
+        assert_eq!(played.decals, game.decals);
+    }
+
+    #[test]
+    fn test_parse_demo_errors() {
+        let broken = [
+            ("tick_rate 30\nmap a.map", "line 1: demo was recorded at 30 ticks per second, the game runs at 60"),
+            ("map a.map\ninput 2 1 0 0", "line 2: expected 'input <ticks> <forward> <strafe> <turn> <pitch> <mouse turn> <mouse look> [jump] [crouch] [fire] [reload] [next|previous]... [select <slot>]'"),
+            ("map a.map\ninput 2 1 0 0 0 0 0 run", "line 2: unknown flag 'run', expected jump, crouch, fire, reload, next, previous or select"),
+            ("map a.map\ninput 2 1 0 0 0 0 0 select 0", "line 2: expected 'input <ticks> <forward> <strafe> <turn> <pitch> <mouse turn> <mouse look> [jump] [crouch] [fire] [reload] [next|previous]... [select <slot>]'"),
+            ("map a.map\nweapons", "line 2: expected 'weapons <path>'"),
+            ("map\n", "line 1: expected 'map <path>'"),
+            ("input 1 0 0 0 0 0 0", "map of the demo is missing"),
+            ("play a.map", "line 1: unknown keyword 'play'"),
+        ];
+        for (source, expected) in broken{
+            assert_eq!(parse_demo(source).unwrap_err(), expected);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use crate::ai::{self, AiState};
+use crate::damage::{self, Health, MAX_ARMOUR, PLAYER_HEALTH};
+use crate::sprite::Sprite;
+use crate::{Game, GameEvent, PlayerState, BLOCKSIZE};
+
+/// Radius of the entities for collisions and shots, in world units
+pub const ENTITY_RADIUS: f32 = 16.0;
+/// Health enemies start with
+pub const ENEMY_HEALTH: i32 = 100;
+
+/// What an entity does every tick
+#[derive(Debug, Clone, PartialEq)]
+pub enum Behaviour{
+    Prop, // stands in the way
+    Pickup(Item), // gives the item to the player walking over it
+    Enemy, // hunts and shoots the player, see ai
+}
+
+/// Names of the behaviours in map files
+pub const BEHAVIOUR_NAMES: [&str; 3] = ["prop", "pickup", "enemy"];
+
+/// What a pickup gives the player
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item{
+    Health(i32), // up to PLAYER_HEALTH
+    Armour(i32), // up to MAX_ARMOUR
+    Ammo(String, u32), // name of the ammo type and rounds, up to AmmoType::max
+}
+
+/// Words after the texture of a pickup in map files
+pub const ITEM_USAGE: &str = "health <amount>|armour <amount>|ammo <ammo type> <rounds>";
+
+impl Item{
+    /// Reads the words after the texture of a pickup in a map file, amounts can't be 0
+    pub fn parse(words: &[&str]) -> Option<Item>{
+        match words{
+            ["health", amount] => amount.parse().ok().filter(|amount| *amount > 0).map(Item::Health),
+            ["armour", amount] => amount.parse().ok().filter(|amount| *amount > 0).map(Item::Armour),
+            ["ammo", name, rounds] => rounds.parse().ok().filter(|rounds| *rounds > 0).map(|rounds| Item::Ammo(name.to_string(), rounds)),
+            _ => None,
+        }
+    }
+}
+
+/// Something besides the player that stands on the floor of the map, drawn as a sprite
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity{
+    pub pos_x: f32,
+    pub pos_y: f32,
+    pub angle: f32, // where it faces, like Player::angle
+    pub texture: usize, // index of the texture in TextureSet::sprites
+    pub radius: f32,
+    pub health: Option<Health>, // None can't be hurt
+    pub behaviour: Behaviour,
+    pub state: AiState, // only enemies leave Idle
+    pub cooldown: f32, // seconds until an enemy can shoot again
+    pub last_seen: Option<(f32, f32)>, // where an enemy saw the player last
+    pub path: Vec<(usize, usize)>, // cells a chasing enemy walks through to its target, the next one first
+    pub path_goal: Option<(usize, usize)>, // cell the path leads to, it is only found again when the target is in another cell
+}
+
+impl Entity{
+    /// Returns an entity of ENTITY_RADIUS, enemies get ENEMY_HEALTH and the others can't be hurt
+    pub fn new(behaviour: Behaviour, pos_x: f32, pos_y: f32, angle: f32, texture: usize) -> Entity{
+        Entity{
+            pos_x,
+            pos_y,
+            angle,
+            texture,
+            radius: ENTITY_RADIUS,
+            health: if behaviour == Behaviour::Enemy {Some(Health::new(ENEMY_HEALTH, 0))} else {None},
+            behaviour,
+            state: AiState::Idle,
+            cooldown: 0.0,
+            last_seen: None,
+            path: Vec::new(),
+            path_goal: None,
+        }
+    }
+
+    /// Returns true if the entity stops the player and shots
+    pub fn solid(&self) -> bool{
+        !matches!(self.behaviour, Behaviour::Pickup(_))
+    }
+
+    pub fn sprite(&self) -> Sprite{
+        Sprite{ pos_x: self.pos_x, pos_y: self.pos_y, texture: self.texture }
+    }
+
+    /// Returns the distance between the centers of the entity and the point
+    pub fn distance_to(&self, pos_x: f32, pos_y: f32) -> f32{
+        ((self.pos_x - pos_x).powi(2) + (self.pos_y - pos_y).powi(2)).sqrt()
+    }
+}
+
+/// Returns true if a solid entity is in the way of the player moving to the position.
+/// Moving away from an entity the player is already in is allowed, so they can't get stuck
+pub fn blocks(game: &Game, pos_x: f32, pos_y: f32) -> bool{
+    // Entities are a block high, players above them walk over
+    if game.player.pos_z >= BLOCKSIZE as f32 {return false;}
+    game.entities.iter().filter(|entity| entity.solid()).any(|entity| {
+        let distance = entity.distance_to(pos_x, pos_y);
+        distance < entity.radius + game.player_radius && distance < entity.distance_to(game.player.pos_x, game.player.pos_y)
+    })
+}
+
+/// Runs the behaviour of every entity for a tick of delta_time seconds
+pub fn update_entities(game: &mut Game, delta_time: f32) -> Vec<GameEvent>{
+    let player = game.player;
+    let mut events: Vec<GameEvent> = Vec::new();
+    let mut shots: Vec<Entity> = Vec::new();
+    for entity in game.entities.iter_mut(){
+        match entity.behaviour{
+            Behaviour::Enemy => {
+                if ai::update_enemy(&game.game_map, &player, game.player_radius, entity, delta_time){
+                    shots.push(entity.clone());
+                }
+            },
+            Behaviour::Prop | Behaviour::Pickup(_) => {},
+        }
+    }
+    for enemy in shots{
+        let source = Some((enemy.pos_x, enemy.pos_y));
+        events.push(GameEvent::EnemyFired(enemy));
+        events.extend(damage::damage_player(game, ai::ENEMY_SHOT_DAMAGE, source));
+    }
+    let player = game.player;
+    // Pickups on the floor are picked up by walking over them, they stay while the player has no room for what they give
+    let radius = game.player_radius;
+    let mut idx = 0;
+    while idx < game.entities.len(){
+        let entity = &game.entities[idx];
+        let touched = player.state == PlayerState::Alive && player.pos_z < BLOCKSIZE as f32
+            && entity.distance_to(player.pos_x, player.pos_y) < entity.radius + radius;
+        let item = match &entity.behaviour{
+            Behaviour::Pickup(item) if touched => Some(item.clone()),
+            _ => None,
+        };
+        if item.is_some_and(|item| give(game, &item)) {events.push(GameEvent::PickedUp(game.entities.remove(idx)));}
+        else {idx += 1;}
+    }
+    events
+}
+
+/// Gives the item to the player, returns false if they have no room for it.
+/// Ammo of a type that isn't in the arsenal can't be picked up
+pub fn give(game: &mut Game, item: &Item) -> bool{
+    let health = &mut game.player.health;
+    match item{
+        Item::Health(amount) => {
+            if health.health >= PLAYER_HEALTH {return false;}
+            health.health = health.health.saturating_add(*amount).min(PLAYER_HEALTH);
+        },
+        Item::Armour(amount) => {
+            if health.armour >= MAX_ARMOUR {return false;}
+            health.armour = health.armour.saturating_add(*amount).min(MAX_ARMOUR);
+        },
+        Item::Ammo(name, rounds) => {
+            let Some(idx) = game.arsenal.ammo_types.iter().position(|ammo_type| ammo_type.name == *name) else {return false;};
+            let (carried, max) = (&mut game.inventory.ammo[idx], game.arsenal.ammo_types[idx].max);
+            if *carried >= max {return false;}
+            *carried = carried.saturating_add(*rounds).min(max);
+        },
+    }
+    true
+}
+
+/// Takes the amount from the health of the entity, returns Killed and removes it when the health runs out.
+/// Idle enemies that survive go after the player
+pub fn damage(game: &mut Game, idx: usize, amount: i32) -> Option<GameEvent>{
+    let player = game.player;
+    let entity = game.entities.get_mut(idx)?;
+    let health = entity.health.as_mut()?;
+    health.take(amount);
+    if !health.is_dead(){
+        if entity.state == AiState::Idle {entity.state = AiState::Chase{ target_x: player.pos_x, target_y: player.pos_y };}
+        return None;
+    }
+    Some(GameEvent::Killed(game.entities.remove(idx)))
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn test_game() -> Game{
+        let mut game = Game::new(Player::new(96.0, 96.0, 0.0), map::GameMap::new(8, 8));
+        game.entities = vec![
+            Entity::new(Behaviour::Prop, 160.0, 96.0, 0.0, 0),
+            Entity::new(Behaviour::Pickup(Item::Health(25)), 96.0, 160.0, 0.0, 1),
+            Entity::new(Behaviour::Enemy, 300.0, 96.0, 90.0, 2),
+        ];
+        game
+    }
+
+    #[test]
+    fn test_blocks() {
+        let mut game = test_game();
+        assert!(blocks(&game, 130.0, 96.0));
+        assert!(!blocks(&game, 126.0, 96.0));
+        // Pickups don't stop the player
+        assert!(!blocks(&game, 96.0, 140.0));
+        assert!(!try_move(&mut game, 130.0, 96.0));
+        // Inside of a prop the player can still get out
+        game.player.pos_x = 150.0;
+        assert!(!blocks(&game, 140.0, 96.0));
+        assert!(blocks(&game, 155.0, 96.0));
+        game.player.pos_z = BLOCKSIZE as f32;
+        assert!(!blocks(&game, 155.0, 96.0));
+    }
+
+    #[test]
+    fn test_update_entities() {
+        let mut game = test_game();
+        // The enemy faces away from the player and doesn't notice them
+        assert!(update_entities(&mut game, 0.5).is_empty());
+        assert_eq!(game.entities[2].state, AiState::Idle);
+        assert_eq!(game.entities[2].angle, 90.0);
+        // Facing them it turns to them and shoots
+        game.entities[2].angle = 150.0;
+        let events = update_entities(&mut game, 0.5);
+        assert_eq!(game.entities[2].state, AiState::Attack);
+        assert_eq!(events, vec![GameEvent::EnemyFired(game.entities[2].clone()), GameEvent::Hurt(ai::ENEMY_SHOT_DAMAGE)]);
+        // Walking over the pickup heals up to the full health, the second one stays until there is room for it
+        game.entities.push(Entity::new(Behaviour::Pickup(Item::Health(25)), 96.0, 170.0, 0.0, 1));
+        game.player.pos_y = 140.0;
+        let events = update_entities(&mut game, 0.1);
+        assert_eq!(events, vec![GameEvent::PickedUp(Entity::new(Behaviour::Pickup(Item::Health(25)), 96.0, 160.0, 0.0, 1))]);
+        assert_eq!(game.player.health.health, PLAYER_HEALTH);
+        assert_eq!(game.entities.len(), 3);
+    }
+
+    #[test]
+    fn test_give() {
+        let mut game = test_game();
+        // Armour adds up to MAX_ARMOUR, then the player has no room for more
+        assert!(give(&mut game, &Item::Armour(60)));
+        assert!(give(&mut game, &Item::Armour(60)));
+        assert_eq!(game.player.health.armour, MAX_ARMOUR);
+        assert!(!give(&mut game, &Item::Armour(60)));
+        // and absorbs half of the damage
+        assert_eq!(damage::damage_player(&mut game, 20, None), Some(GameEvent::Hurt(10)));
+        assert_eq!(game.player.health, Health::new(PLAYER_HEALTH - 10, MAX_ARMOUR - 10));
+        // Huge amounts from map files fill up without overflowing
+        assert!(give(&mut game, &Item::Health(i32::MAX)));
+        assert!(give(&mut game, &Item::Armour(i32::MAX)));
+        assert_eq!(game.player.health, Health::new(PLAYER_HEALTH, MAX_ARMOUR));
+        // Ammo is carried up to the most of its type
+        game.arm(weapon::load_weapons(weapon::DEFAULT_WEAPONS).unwrap());
+        let bullets = game.arsenal.ammo_types[0].clone();
+        assert!(give(&mut game, &Item::Ammo(bullets.name.clone(), bullets.max)));
+        assert_eq!(game.inventory.ammo[0], bullets.max);
+        assert!(!give(&mut game, &Item::Ammo(bullets.name.clone(), 10)));
+        game.inventory.ammo[0] = 1;
+        assert!(give(&mut game, &Item::Ammo(bullets.name, u32::MAX)));
+        assert_eq!(game.inventory.ammo[0], bullets.max);
+        assert!(!give(&mut game, &Item::Ammo("rockets".to_string(), 10)));
+    }
+
+    #[test]
+    fn test_damage() {
+        let mut game = test_game();
+        // Props can't be hurt
+        assert_eq!(damage(&mut game, 0, 1000), None);
+        assert_eq!(damage(&mut game, 2, ENEMY_HEALTH - 1), None);
+        assert_eq!(game.entities[2].health, Some(Health::new(1, 0)));
+        // Hurting an enemy makes it go after the player
+        assert_eq!(game.entities[2].state, AiState::Chase{ target_x: 96.0, target_y: 96.0 });
+        assert!(matches!(damage(&mut game, 2, 1), Some(GameEvent::Killed(Entity{ behaviour: Behaviour::Enemy, .. }))));
+        assert_eq!(game.entities.len(), 2);
+        assert_eq!(damage(&mut game, 5, 1), None);
+    }
+
+    #[test]
+    fn test_shoot_enemy() {
+        // The prop would stop the shots before the enemy
+        let mut game = test_game();
+        game.entities[0].pos_y = 300.0;
+        let shot = Input{ fire: true, ..Input::default() };
+        for _ in 1..ENEMY_HEALTH / hitscan::SHOT_DAMAGE{
+            let events = update(&mut game, &shot, TICK_TIME);
+            assert!(matches!(events[1], GameEvent::Hit(hitscan::Hit{ target: hitscan::Target::Entity(2), .. })));
+        }
+        let events = update(&mut game, &shot, TICK_TIME);
+        assert!(matches!(events.last(), Some(GameEvent::Killed(Entity{ behaviour: Behaviour::Enemy, .. }))));
+        assert_eq!(game.entities.len(), 2);
+    }
+}
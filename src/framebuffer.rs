@@ -0,0 +1,109 @@
+use std::fs::File;
+use std::io::BufWriter;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+/// RGBA pixel buffer the raycaster draws into, doesn't need a window or SDL2 to be initialized
+#[derive(Debug, Clone)]
+pub struct FrameBuffer{
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>, // 4 bytes(R, G, B, A) per pixel, row by row
+}
+
+impl FrameBuffer{
+    pub fn new(width: u32, height: u32) -> FrameBuffer{
+        FrameBuffer{
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Loads a PNG image, 16 bit and RGB images are converted to 8 bit RGBA
+    pub fn load_png(path: &str) -> Result<FrameBuffer, String>{
+        let file = File::open(path).map_err(|e| format!("Couldn't open {}: {}", path, e))?;
+        let mut decoder = png::Decoder::new(file);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).map_err(|e| format!("Couldn't decode {}: {}", path, e))?;
+        let channels = match info.color_type{
+            png::ColorType::Rgba => 4,
+            png::ColorType::Rgb => 3,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::Grayscale => 1,
+            png::ColorType::Indexed => return Err(format!("Couldn't decode {}: unexpanded palette", path)),
+        };
+        let mut image = FrameBuffer::new(info.width, info.height);
+        for (idx, pixel) in data[..info.buffer_size()].chunks(channels).enumerate(){
+            let rgba = match channels{
+                4 => [pixel[0], pixel[1], pixel[2], pixel[3]],
+                3 => [pixel[0], pixel[1], pixel[2], 255],
+                2 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                _ => [pixel[0], pixel[0], pixel[0], 255],
+            };
+            image.pixels[idx * 4..idx * 4 + 4].copy_from_slice(&rgba);
+        }
+        Ok(image)
+    }
+
+    /// Saves the buffer as a 8 bit RGBA PNG image
+    pub fn save_png(&self, path: &str) -> Result<(), String>{
+        let file = File::create(path).map_err(|e| format!("Couldn't create {}: {}", path, e))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| format!("Couldn't write {}: {}", path, e))?;
+        writer.write_image_data(&self.pixels).map_err(|e| format!("Couldn't write {}: {}", path, e))
+    }
+
+    /// Fills the whole buffer with the given color
+    pub fn clear(&mut self, color: Color){
+        for pixel in self.pixels.chunks_mut(4){
+            pixel.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Color{
+        let idx = ((y * self.width + x) * 4) as usize;
+        Color::RGBA(self.pixels[idx], self.pixels[idx + 1], self.pixels[idx + 2], self.pixels[idx + 3])
+    }
+
+    /// Sets a pixel, pixels outside of the buffer are ignored
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color){
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {return;}
+        let idx = ((y as u32 * self.width + x as u32) * 4) as usize;
+        self.pixels[idx..idx + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: Color){
+        for y in rect.top()..rect.bottom(){
+            for x in rect.left()..rect.right(){
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Copies src part of the image to dst part of the buffer, scales if their sizes differ and blends with alpha
+    pub fn blit(&mut self, image: &FrameBuffer, src: Rect, dst: Rect){
+        for y in 0..dst.height(){
+            for x in 0..dst.width(){
+                let src_x = src.x() as u32 + x * src.width() / dst.width();
+                let src_y = src.y() as u32 + y * src.height() / dst.height();
+                if src_x >= image.width || src_y >= image.height {continue;}
+                let color = image.get_pixel(src_x, src_y);
+                if color.a == 0 {continue;}
+                let (dst_x, dst_y) = (dst.x() + x as i32, dst.y() + y as i32);
+                if color.a == 255 || dst_x < 0 || dst_y < 0 || dst_x >= self.width as i32 || dst_y >= self.height as i32{
+                    self.set_pixel(dst_x, dst_y, color);
+                    continue;
+                }
+                let below = self.get_pixel(dst_x as u32, dst_y as u32);
+                let alpha = color.a as u32;
+                let blend = |top: u8, bottom: u8| ((top as u32 * alpha + bottom as u32 * (255 - alpha)) / 255) as u8;
+                self.set_pixel(dst_x, dst_y, Color::RGB(blend(color.r, below.r), blend(color.g, below.g), blend(color.b, below.b)));
+            }
+        }
+    }
+}
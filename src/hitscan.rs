@@ -0,0 +1,203 @@
+use crate::{Game, Player, Ray, BLOCKSIZE, cast_ray, get_deltas};
+
+/// Radius of the sprites for shots, in world units
+pub const SPRITE_RADIUS: f32 = 16.0;
+/// Health a shot takes from the entity it hits
+pub const SHOT_DAMAGE: i32 = 25;
+/// Walls keep only the latest decals
+pub const MAX_DECALS: usize = 32;
+/// Radius of the bullet holes, in world units
+pub const DECAL_RADIUS: f32 = 3.0;
+/// Sounds further than this aren't heard
+const HEARING_DISTANCE: f32 = 1024.0;
+
+/// What a shot hit
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Target{
+    Wall{ map_x: i32, map_y: i32, hit_side: i32 },
+    Sprite(usize), // index in Game::sprites
+    Entity(usize), // index in Game::entities
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hit{
+    pub target: Target,
+    pub pos_x: f32, // where the shot hit
+    pub pos_y: f32,
+    pub distance: f32,
+}
+
+/// Bullet hole on the side of a first level wall
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Decal{
+    pub map_x: i32,
+    pub map_y: i32,
+    pub hit_side: i32,
+    pub pos_x: f32, // position on the side of the wall
+    pub pos_y: f32,
+    pub height: f32, // height from the floor
+}
+
+impl Decal{
+    /// Returns how far along the wall the ray hit from the decal, None if the ray hit another side
+    pub fn offset(&self, ray: &Ray) -> Option<f32>{
+        if (ray.map_x, ray.map_y, ray.hit_side) != (self.map_x, self.map_y, self.hit_side) {return None;}
+        // Opposite sides of a block are a block apart
+        let (across, along) = if self.hit_side == 1 {(ray.pos_x - self.pos_x, ray.pos_y - self.pos_y)} else {(ray.pos_y - self.pos_y, ray.pos_x - self.pos_x)};
+        if across.abs() > 1.0 {return None;}
+        Some(along)
+    }
+}
+
+/// Casts a shot from the player to the given angle at eye height, returns the closest wall, sprite or solid entity it hit
+pub fn hitscan(game: &Game, angle: f32) -> Option<Hit>{
+    let (dir_x, dir_y) = get_deltas(angle);
+    // Shots fly at eye height, so only the walls of that level can stop them
+    let eye_height = game.player.eye_height();
+    let level_idx = (eye_height / BLOCKSIZE as f32) as usize;
+    let wall = cast_ray(&game.game_map, game.player.pos_x, game.player.pos_y, angle).get(level_idx).copied().unwrap_or(Ray::new());
+    let mut closest: Option<Hit> = None;
+    if wall.hit_side != -1{
+        closest = Some(Hit{
+            target: Target::Wall{ map_x: wall.map_x, map_y: wall.map_y, hit_side: wall.hit_side },
+            pos_x: wall.pos_x,
+            pos_y: wall.pos_y,
+            distance: wall.distance,
+        });
+    }
+    // Sprites and entities stand on the floor and are a block high
+    if eye_height >= BLOCKSIZE as f32 {return closest;}
+    let sprites = game.sprites.iter().enumerate().map(|(idx, sprite)| (Target::Sprite(idx), sprite.pos_x, sprite.pos_y, SPRITE_RADIUS));
+    let entities = game.entities.iter().enumerate().filter(|(_, entity)| entity.solid())
+        .map(|(idx, entity)| (Target::Entity(idx), entity.pos_x, entity.pos_y, entity.radius));
+    for (target, pos_x, pos_y, radius) in sprites.chain(entities){
+        let (offset_x, offset_y) = (pos_x - game.player.pos_x, pos_y - game.player.pos_y);
+        // Closest point of the shot to the center of the sprite
+        let along = offset_x * dir_x + offset_y * dir_y;
+        let across_squared = offset_x * offset_x + offset_y * offset_y - along * along;
+        if along < 0.0 || across_squared > radius * radius {continue;}
+        let distance = along - (radius * radius - across_squared).sqrt();
+        if closest.is_none_or(|hit| distance < hit.distance){
+            closest = Some(Hit{
+                target,
+                pos_x: game.player.pos_x + dir_x * distance,
+                pos_y: game.player.pos_y + dir_y * distance,
+                distance,
+            });
+        }
+    }
+    closest
+}
+
+/// Leaves a bullet hole where the shot hit, if it hit a wall
+pub fn add_decal(game: &mut Game, hit: &Hit){
+    let height = game.player.eye_height();
+    if let Target::Wall{ map_x, map_y, hit_side } = hit.target{
+        if game.decals.len() == MAX_DECALS {game.decals.remove(0);}
+        game.decals.push(Decal{ map_x, map_y, hit_side, pos_x: hit.pos_x, pos_y: hit.pos_y, height });
+    }
+}
+
+/// Returns volume(0 to 1) and pan(-1 left to 1 right) of a sound playing at the given position
+pub fn sound_at(player: &Player, pos_x: f32, pos_y: f32) -> (f32, f32){
+    let (offset_x, offset_y) = (pos_x - player.pos_x, pos_y - player.pos_y);
+    let distance = (offset_x * offset_x + offset_y * offset_y).sqrt();
+    let volume = (1.0 - distance / HEARING_DISTANCE).max(0.0);
+    if distance < 1.0 {return (volume, 0.0);}
+    // Right of the player is -90 degrees of the looking direction
+    let (dir_x, dir_y) = get_deltas(player.angle);
+    let right = (offset_x * -dir_y + offset_y * dir_x) / distance;
+    (volume, right)
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use crate::sprite::Sprite;
+    use crate::entity::{Behaviour, Entity, Item, ENTITY_RADIUS};
+
+    fn test_game() -> Game{
+        Game::new(
+            Player::new(300.0, 300.0, 0.0),
+            map::load_map(map::DEFAULT_MAP).unwrap().game_map
+        )
+    }
+
+    #[test]
+    fn test_hitscan_wall() {
+        let game = test_game();
+        let hit = hitscan(&game, 0.0).unwrap();
+        assert_eq!(hit.target, Target::Wall{ map_x: 15, map_y: 4, hit_side: 1 });
+        assert_eq!((hit.pos_x, hit.pos_y, hit.distance), (960.0, 300.0, 660.0));
+    }
+
+    #[test]
+    fn test_hitscan_sprites() {
+        let mut game = test_game();
+        game.sprites = vec![
+            Sprite{ pos_x: 600.0, pos_y: 300.0, texture: 0 },
+            Sprite{ pos_x: 500.0, pos_y: 310.0, texture: 0 },
+            Sprite{ pos_x: 400.0, pos_y: 400.0, texture: 0 }, // too far from the line of the shot
+        ];
+        let hit = hitscan(&game, 0.0).unwrap();
+        assert_eq!(hit.target, Target::Sprite(1));
+        assert!((hit.distance - (200.0 - (16.0_f32 * 16.0 - 10.0 * 10.0).sqrt())).abs() < 0.001);
+        // Sprites behind the walls can't be shot
+        game.sprites = vec![Sprite{ pos_x: 1000.0, pos_y: 300.0, texture: 0 }];
+        assert!(matches!(hitscan(&game, 0.0).unwrap().target, Target::Wall{ .. }));
+        // Shots go through pickups and hit the solid entities behind them
+        game.entities = vec![
+            Entity::new(Behaviour::Pickup(Item::Health(25)), 400.0, 300.0, 0.0, 0),
+            Entity::new(Behaviour::Enemy, 450.0, 300.0, 0.0, 0),
+        ];
+        let hit = hitscan(&game, 0.0).unwrap();
+        assert_eq!(hit.target, Target::Entity(1));
+        assert_eq!(hit.distance, 150.0 - ENTITY_RADIUS);
+    }
+
+    #[test]
+    fn test_hitscan_height() {
+        // Standing on top of the first level, shots fly over the sprites and the walls that only have one level
+        let mut game = test_game();
+        game.sprites = vec![Sprite{ pos_x: 500.0, pos_y: 300.0, texture: 0 }];
+        game.player.pos_z = BLOCKSIZE as f32;
+        assert_eq!(hitscan(&game, 0.0), None);
+        game.player.pos_y = 544.0;
+        game.player.pos_x = 600.0;
+        assert_eq!(hitscan(&game, 0.0).unwrap().target, Target::Wall{ map_x: 10, map_y: 8, hit_side: 1 });
+    }
+
+    #[test]
+    fn test_add_decal() {
+        let mut game = test_game();
+        let hit = hitscan(&game, 0.0).unwrap();
+        for _ in 0..MAX_DECALS + 1{
+            add_decal(&mut game, &hit);
+        }
+        assert_eq!(game.decals.len(), MAX_DECALS);
+        assert_eq!(game.decals[0], Decal{ map_x: 15, map_y: 4, hit_side: 1, pos_x: 960.0, pos_y: 300.0, height: CAMERA_HEIGHT });
+        add_decal(&mut game, &Hit{ target: Target::Sprite(0), pos_x: 0.0, pos_y: 0.0, distance: 0.0 });
+        assert_eq!(game.decals.len(), MAX_DECALS);
+        // Rays hitting the same side near the hole see it, other sides don't
+        let decal = game.decals[0];
+        let mut ray = cast_ray(&game.game_map, 300.0, 310.0, 0.0)[0];
+        assert_eq!(decal.offset(&ray), Some(10.0));
+        ray.pos_x -= BLOCKSIZE as f32;
+        assert_eq!(decal.offset(&ray), None);
+    }
+
+    #[test]
+    fn test_sound_at() {
+        // Looking to +X, +Y is on the right
+        let player = test_game().player;
+        let (volume, pan) = sound_at(&player, 300.0, 400.0);
+        assert!((volume - (1.0 - 100.0 / HEARING_DISTANCE)).abs() < 0.001);
+        assert!((pan - 1.0).abs() < 0.001);
+        let (_, pan) = sound_at(&player, 400.0, 300.0);
+        assert!(pan.abs() < 0.001);
+        assert_eq!(sound_at(&player, 300.0, 300.0 - HEARING_DISTANCE * 2.0).0, 0.0);
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use sdl2::keyboard::Scancode;
+use sdl2::mouse::MouseButton;
+use sdl2::controller::{Axis, Button};
+
+pub const DEFAULT_BINDINGS: &str = "assets/bindings.cfg";
+/// Degrees the player turns for a pixel of mouse motion by default
+pub const DEFAULT_SENSITIVITY: f32 = 0.15;
+
+/// Turning the player and tilting the view with the mouse in relative mouse mode
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MouseLook{
+    pub enabled: bool, // grabs the mouse while playing
+    pub sensitivity: f32, // degrees per pixel of mouse motion
+    pub invert: bool, // turns left when the mouse moves right
+    pub invert_pitch: bool, // looks up when the mouse moves down
+}
+
+impl Default for MouseLook{
+    fn default() -> MouseLook{
+        MouseLook{
+            enabled: true,
+            sensitivity: DEFAULT_SENSITIVITY,
+            invert: false,
+            invert_pitch: false,
+        }
+    }
+}
+
+impl MouseLook{
+    /// Returns the degrees the player turns for a relative mouse motion, positive turns left like Player::angle
+    pub fn turn(&self, xrel: i32) -> f32{
+        if !self.enabled {return 0.0;}
+        // Moving the mouse to the right turns to the right, which is a smaller angle
+        let degrees = -xrel as f32 * self.sensitivity;
+        if self.invert {-degrees} else {degrees}
+    }
+
+    /// Returns the degrees the player looks up(positive) or down for a relative mouse motion
+    pub fn pitch(&self, yrel: i32) -> f32{
+        if !self.enabled {return 0.0;}
+        // Screen y grows downwards, moving the mouse up looks up
+        let degrees = -yrel as f32 * self.sensitivity;
+        if self.invert_pitch {-degrees} else {degrees}
+    }
+}
+
+/// What the player can do, keys and mouse buttons are bound to these
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action{
+    MoveForward,
+    MoveBackward,
+    StrafeLeft,
+    StrafeRight,
+    TurnLeft,
+    TurnRight,
+    LookUp,
+    LookDown,
+    Jump,
+    Crouch,
+    Fire,
+    Reload,
+    NextWeapon,
+    PreviousWeapon,
+    Weapon(usize), // index of the weapon in the weapons file
+    Use, // interacting with the world, nothing in the game uses it yet
+    Pause,
+    Quit,
+}
+
+/// Names of the actions in the bindings file
+const ACTION_NAMES: [(&str, Action); 26] = [
+    ("move_forward", Action::MoveForward),
+    ("move_backward", Action::MoveBackward),
+    ("strafe_left", Action::StrafeLeft),
+    ("strafe_right", Action::StrafeRight),
+    ("turn_left", Action::TurnLeft),
+    ("turn_right", Action::TurnRight),
+    ("look_up", Action::LookUp),
+    ("look_down", Action::LookDown),
+    ("jump", Action::Jump),
+    ("crouch", Action::Crouch),
+    ("fire", Action::Fire),
+    ("reload", Action::Reload),
+    ("next_weapon", Action::NextWeapon),
+    ("previous_weapon", Action::PreviousWeapon),
+    ("weapon_1", Action::Weapon(0)),
+    ("weapon_2", Action::Weapon(1)),
+    ("weapon_3", Action::Weapon(2)),
+    ("weapon_4", Action::Weapon(3)),
+    ("weapon_5", Action::Weapon(4)),
+    ("weapon_6", Action::Weapon(5)),
+    ("weapon_7", Action::Weapon(6)),
+    ("weapon_8", Action::Weapon(7)),
+    ("weapon_9", Action::Weapon(8)),
+    ("use", Action::Use),
+    ("pause", Action::Pause),
+    ("quit", Action::Quit),
+];
+
+/// Values of SDL_Scancode, Scancode::from_i32 can't be given anything else
+const SCANCODE_RANGES: [std::ops::RangeInclusive<i32>; 5] = [4..=129, 133..=164, 176..=221, 224..=231, 257..=286];
+
+const MOUSE_BUTTONS: [MouseButton; 5] = [MouseButton::Left, MouseButton::Middle, MouseButton::Right, MouseButton::X1, MouseButton::X2];
+
+pub const CONTROLLER_BUTTONS: [Button; 21] = [
+    Button::A, Button::B, Button::X, Button::Y, Button::Back, Button::Guide, Button::Start,
+    Button::LeftStick, Button::RightStick, Button::LeftShoulder, Button::RightShoulder,
+    Button::DPadUp, Button::DPadDown, Button::DPadLeft, Button::DPadRight,
+    Button::Misc1, Button::Paddle1, Button::Paddle2, Button::Paddle3, Button::Paddle4, Button::Touchpad,
+];
+
+/// Axes of a game controller that are pressed like buttons
+pub const TRIGGERS: [Axis; 2] = [Axis::TriggerLeft, Axis::TriggerRight];
+
+/// A key, a mouse button or a game controller button an action is bound to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Binding{
+    Key(Scancode),
+    Mouse(MouseButton),
+    WheelUp, // a notch of the mouse wheel, it is pressed but never held
+    WheelDown,
+    Controller(Button),
+    Trigger(Axis), // pressed past controller::TRIGGER_THRESHOLD
+}
+
+impl Binding{
+    /// Returns the binding with the name, keys are named like Scancode(W, Space, LCtrl, PageUp...), mouse buttons like MouseLeft,
+    /// the mouse wheel MouseWheelUp and MouseWheelDown and controller buttons like PadA or PadTriggerRight
+    pub fn from_name(name: &str) -> Option<Binding>{
+        if name.eq_ignore_ascii_case("MouseWheelUp") {return Some(Binding::WheelUp);}
+        if name.eq_ignore_ascii_case("MouseWheelDown") {return Some(Binding::WheelDown);}
+        if let Some(button) = name.strip_prefix("Pad"){
+            let named = |binding: &Binding| match binding {
+                Binding::Controller(button) => format!("{:?}", button),
+                Binding::Trigger(axis) => format!("{:?}", axis),
+                _ => String::new(),
+            }.eq_ignore_ascii_case(button);
+            return CONTROLLER_BUTTONS.iter().map(|button| Binding::Controller(*button)).chain(TRIGGERS.iter().map(|axis| Binding::Trigger(*axis))).find(named);
+        }
+        if let Some(button) = name.strip_prefix("Mouse"){
+            return MOUSE_BUTTONS.iter().find(|mouse_button| format!("{:?}", mouse_button).eq_ignore_ascii_case(button)).map(|mouse_button| Binding::Mouse(*mouse_button));
+        }
+        // SDL_GetScancodeFromName needs SDL, the names of the enum work without it
+        SCANCODE_RANGES.into_iter().flatten().filter_map(Scancode::from_i32).find(|scancode| format!("{:?}", scancode).eq_ignore_ascii_case(name)).map(Binding::Key)
+    }
+}
+
+/// Actions and the keys and mouse buttons that do them, an action can have several bindings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bindings{
+    actions: HashMap<Binding, Vec<Action>>,
+}
+
+impl Bindings{
+    /// Binds the key or button to the action, on top of what it already does
+    pub fn bind(&mut self, action: Action, binding: Binding){
+        let actions = self.actions.entry(binding).or_default();
+        if !actions.contains(&action) {actions.push(action);}
+    }
+
+    /// Returns the actions the key or button does
+    pub fn actions(&self, binding: Binding) -> &[Action]{
+        self.actions.get(&binding).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the actions that are held down by the pressed keys and mouse buttons
+    pub fn held(&self, pressed: impl IntoIterator<Item = Binding>) -> HashSet<Action>{
+        pressed.into_iter().flat_map(|binding| self.actions(binding).iter().copied()).collect()
+    }
+}
+
+/// Reads a bindings file, errors start with the path of the file
+pub fn load_bindings(path: &str) -> Result<Bindings, String>{
+    let source = fs::read_to_string(path).map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+    parse_bindings(&source).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Parses the text of a bindings file, every line is 'bind <action> <key or button>...'
+pub fn parse_bindings(source: &str) -> Result<Bindings, String>{
+    let mut bindings = Bindings::default();
+    for (line_idx, line) in source.lines().enumerate(){
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {continue;}
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let error = |message: String| format!("line {}: {}", line_idx + 1, message);
+        if words[0] != "bind" {return Err(error(format!("unknown keyword '{}'", words[0])));}
+        if words.len() < 3 {return Err(error("expected 'bind <action> <key or button>...'".to_string()));}
+        let action = ACTION_NAMES.iter().find(|(name, _)| *name == words[1]).map(|(_, action)| *action)
+            .ok_or_else(|| error(format!("unknown action '{}'", words[1])))?;
+        for name in &words[2..]{
+            let binding = Binding::from_name(name).ok_or_else(|| error(format!("unknown key or button '{}'", name)))?;
+            bindings.bind(action, binding);
+        }
+    }
+    Ok(bindings)
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn test_mouse_turn() {
+        let mut mouse_look = MouseLook{ enabled: true, sensitivity: 0.5, invert: false, invert_pitch: false };
+        assert_eq!(mouse_look.turn(10), -5.0);
+        assert_eq!(mouse_look.turn(-4), 2.0);
+        assert_eq!(mouse_look.pitch(-4), 2.0);
+        mouse_look.invert = true;
+        assert_eq!(mouse_look.turn(10), 5.0);
+        assert_eq!(mouse_look.pitch(-4), 2.0);
+        mouse_look.invert_pitch = true;
+        assert_eq!(mouse_look.pitch(-4), -2.0);
+        mouse_look.enabled = false;
+        assert_eq!(mouse_look.turn(10), 0.0);
+        assert_eq!(mouse_look.pitch(10), 0.0);
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        let bindings = parse_bindings("# Comment\nbind move_forward W Up\nbind fire MouseLeft LCtrl PadTriggerRight\n\nbind jump lctrl PadA\nbind weapon_2 Num2\nbind next_weapon MouseWheelUp").unwrap();
+        assert_eq!(bindings.actions(Binding::Key(Scancode::W)), &[Action::MoveForward]);
+        assert_eq!(bindings.actions(Binding::Key(Scancode::Up)), &[Action::MoveForward]);
+        assert_eq!(bindings.actions(Binding::Mouse(MouseButton::Left)), &[Action::Fire]);
+        assert_eq!(bindings.actions(Binding::Trigger(Axis::TriggerRight)), &[Action::Fire]);
+        assert_eq!(bindings.actions(Binding::Controller(Button::A)), &[Action::Jump]);
+        // A key can do several actions
+        assert_eq!(bindings.actions(Binding::Key(Scancode::LCtrl)), &[Action::Fire, Action::Jump]);
+        assert_eq!(bindings.actions(Binding::Key(Scancode::S)), &[]);
+        assert_eq!(bindings.actions(Binding::Key(Scancode::Num2)), &[Action::Weapon(1)]);
+        assert_eq!(bindings.actions(Binding::WheelUp), &[Action::NextWeapon]);
+        let held = bindings.held([Binding::Key(Scancode::Up), Binding::Key(Scancode::W), Binding::Key(Scancode::LCtrl)]);
+        assert_eq!(held, HashSet::from([Action::MoveForward, Action::Fire, Action::Jump]));
+    }
+
+    #[test]
+    fn test_parse_bindings_errors() {
+        let broken = [
+            ("bind fire", "line 1: expected 'bind <action> <key or button>...'"),
+            ("bind shoot MouseLeft", "line 1: unknown action 'shoot'"),
+            ("\nbind fire MouseLeft MouseX3", "line 2: unknown key or button 'MouseX3'"),
+            ("bind fire Foo", "line 1: unknown key or button 'Foo'"),
+            ("bind fire PadTriggerUp", "line 1: unknown key or button 'PadTriggerUp'"),
+            ("fire MouseLeft", "line 1: unknown keyword 'fire'"),
+        ];
+        for (source, expected) in broken{
+            assert_eq!(parse_bindings(source).unwrap_err(), expected);
+        }
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        // Every action can be done with the default bindings
+        let bindings = load_bindings(DEFAULT_BINDINGS).unwrap();
+        for (name, action) in ACTION_NAMES{
+            assert!(bindings.actions.values().any(|actions| actions.contains(&action)), "{} isn't bound", name);
+        }
+        assert_eq!(bindings.actions(Binding::Key(Scancode::Escape)), &[Action::Quit]);
+    }
+
+    #[test]
+    fn test_player_turn() {
+        // Big mouse motions can turn more than a full circle in a frame
+        let mut player = Player::new(0.0, 0.0, 10.0);
+        player.turn(MouseLook::default().turn(100));
+        assert!((player.angle - 355.0).abs() < 0.001);
+        player.turn(725.0);
+        assert!((player.angle - 0.0).abs() < 0.001);
+        assert!((player.dir_x - 1.0).abs() < 0.001 && player.dir_y.abs() < 0.001);
+    }
+}
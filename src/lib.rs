@@ -1,19 +1,35 @@
 use std::fmt;
 use std::collections::HashSet;
 use sdl2::pixels::Color;
-use sdl2::rect::{Rect, Point};
-use sdl2::video::Window;
-use sdl2::render::{Canvas, Texture};
-use sdl2::keyboard::Scancode;
+use sdl2::rect::Rect;
 pub mod map;
-pub mod texture_1;
-use texture_1::TEXTURE_1;
+pub mod texture;
+pub mod sprite;
+pub mod framebuffer;
+pub mod presenter;
+pub mod render;
+pub mod hitscan;
+pub mod input;
+pub mod controller;
+pub mod demo;
+pub mod entity;
+pub mod ai;
+pub mod damage;
+pub mod weapon;
+pub mod view_model;
+use framebuffer::FrameBuffer;
+use render::RenderTarget;
+use texture::TextureSet;
+use sprite::{Sprite, draw_sprites};
+use input::{Action, Binding, Bindings};
+use controller::Controllers;
+use entity::Entity;
+use damage::Health;
+use weapon::{Arsenal, Inventory};
+use view_model::ViewModel;
 pub const WINDOW_HEIGHT: u32 = 512;
 pub const WINDOW_WIDTH: u32 = 720;
 
-pub const MAP_LENGTH: usize = 16;
-pub const MAP_WIDTH: usize = 16;
-
 
 pub const BLACK: Color = Color::RGB(0, 0, 0);
 pub const WHITE: Color =  Color::RGB(255, 255, 255);
@@ -25,38 +41,132 @@ pub const BLUE: Color =  Color::RGB(0, 0, 255);
 
 
 pub const BLOCKSIZE: u32 = 64;
-const PLAYER_SPEED: f32 = 4.0;
-const ROTATION_SPEED: f32 = 3.0;
-pub const RAY_COUNT: usize = 90; // Ray Count must be even
-const BULLET_SPEED: f32 = 1.0;
-const RAY_DRAWING_WIDTH: u32 = 8; // Basically WINDOW_WIDTH / RayCount
-
-const WORLDSIZE: u32 = BLOCKSIZE * MAP_LENGTH as u32;
-
+pub const PLAYER_SPEED: f32 = 240.0; // Units per second
+const ROTATION_SPEED: f32 = 180.0; // Degrees per second
+const PITCH_SPEED: f32 = 90.0; // Degrees per second
+pub const MAX_PITCH: f32 = 30.0; // Degrees the player can look up or down
+const ACCELERATION: f32 = 12.0; // How fast the velocity reaches PLAYER_SPEED, per second
+const FRICTION: f32 = 10.0; // How fast the velocity stops without input, per second
+pub const MAX_FRAME_TIME: f32 = 0.1; // Longer frames are simulated as this, so the player can't skip through walls
+pub const TICK_RATE: u32 = 60; // Updates of the game per second, however fast the frames are rendered
+pub const TICK_TIME: f32 = 1.0 / TICK_RATE as f32;
+pub const FOV: f32 = 67.5; // Horizontal field of view in degrees
+pub const CAMERA_HEIGHT: f32 = BLOCKSIZE as f32 / 2.0; // Height of the eyes from where the player stands
+pub const PLAYER_HEIGHT: f32 = BLOCKSIZE as f32 * 0.75; // Fits below an overhang of one level
+pub const CROUCH_CAMERA_HEIGHT: f32 = BLOCKSIZE as f32 / 4.0; // Height of the eyes while crouching
+const CROUCH_SPEED: f32 = 96.0; // Units per second the eyes go down or up when crouching
+const CROUCH_MOVE_FACTOR: f32 = 0.5; // Crouching players move at this fraction of PLAYER_SPEED
+pub const PLAYER_RADIUS: f32 = BLOCKSIZE as f32 / 4.0; // Default Game::player_radius
+const STEP_HEIGHT: f32 = BLOCKSIZE as f32 / 4.0; // Ledges up to this are climbed by walking
+const GRAVITY: f32 = 1600.0; // Units per second squared
+const JUMP_HEIGHT: f32 = BLOCKSIZE as f32 * 0.875; // High enough to get on a block of one level with STEP_HEIGHT
+const DEATH_CAMERA_HEIGHT: f32 = BLOCKSIZE as f32 / 8.0; // The eyes of dead players sink to this
+pub const RESPAWN_DELAY: f32 = 1.0; // Seconds after dying before the player can respawn
+pub const CEILING_HEIGHT: f32 = BLOCKSIZE as f32; // Ceilings are on top of the first level
 const MINIMAP_SIZE: u32 = 128;
-const MINIMAP_OFFSET_X: i32 = (WINDOW_WIDTH - MINIMAP_SIZE) as i32; 
 const MINIMAP_OFFSET_Y: i32 = 0;
-const MINIMAP_BLOCK_SIZE: u32 = 8; // 	inversely proportional with MAP_LENGTH & MAP_WIDTH
 
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Player{
     pub pos_x: f32, // X position
     pub pos_y: f32, // Y position
+    pub pos_z: f32, // height of where the player stands
     pub angle: f32, // Player angle
+    pub pitch: f32, // Degrees the player looks up(positive) or down, moves the horizon of the screen
     pub dir_x: f32, // Delta X
     pub dir_y: f32, // Delta Y
+    pub vel_x: f32, // Velocity, units per second
+    pub vel_y: f32,
+    pub vel_z: f32, // positive while going up
+    pub camera_height: f32, // height of the eyes from where the player stands, lower while crouching
     pub fired: bool,
+    pub health: Health,
+    pub state: PlayerState,
+    pub hazard_cooldown: f32, // seconds until the hazards can hurt the player again
+
+}
+
+/// Whether the player is playing or watching the death cam
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PlayerState{
+    Alive,
+    Dead{ time: f32, killer: Option<(f32, f32)> }, // seconds since dying, where the damage that killed came from
+}
+
+impl Player{
+    /// Returns a player standing still on the floor at the position, looking to the angle
+    pub fn new(pos_x: f32, pos_y: f32, angle: f32) -> Player{
+        let (dir_x, dir_y) = get_deltas(angle);
+        Player{
+            pos_x,
+            pos_y,
+            pos_z: 0.0,
+            angle,
+            pitch: 0.0,
+            dir_x,
+            dir_y,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            vel_z: 0.0,
+            camera_height: CAMERA_HEIGHT,
+            fired: false,
+            health: Health::new(damage::PLAYER_HEALTH, 0),
+            state: PlayerState::Alive,
+            hazard_cooldown: 0.0,
+        }
+    }
+
+    /// Turns the player by the degrees, positive turns left
+    pub fn turn(&mut self, degrees: f32){
+        self.angle = (self.angle + degrees).rem_euclid(360.0);
+        (self.dir_x, self.dir_y) = get_deltas(self.angle);
+    }
+
+    /// Tilts the view up(positive) or down by the degrees, up to MAX_PITCH
+    pub fn look(&mut self, degrees: f32){
+        self.pitch = (self.pitch + degrees).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Returns the player between this and the next state, alpha 0 is this and 1 is next
+    pub fn lerp(&self, next: &Player, alpha: f32) -> Player{
+        let mix = |from: f32, to: f32| from + (to - from) * alpha;
+        // Turning over 0 degrees goes the short way around
+        let turn = (next.angle - self.angle + 180.0).rem_euclid(360.0) - 180.0;
+        let mut player = Player{
+            pos_x: mix(self.pos_x, next.pos_x),
+            pos_y: mix(self.pos_y, next.pos_y),
+            pos_z: mix(self.pos_z, next.pos_z),
+            pitch: mix(self.pitch, next.pitch),
+            camera_height: mix(self.camera_height, next.camera_height),
+            ..*next
+        };
+        player.angle = self.angle;
+        player.turn(turn * alpha);
+        player
+    }
+
+    /// Returns the height of the eyes from the floor
+    pub fn eye_height(&self) -> f32{
+        self.pos_z + self.camera_height
+    }
 
+    /// Returns how tall the player is, the head is above the eyes by the same amount when crouching
+    pub fn body_height(&self) -> f32{
+        self.camera_height + PLAYER_HEIGHT - CAMERA_HEIGHT
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct Ray{
     pub angle: f32,
     pub distance: f32, // distance between the player and where the ray hit
-    pub hit_side: i32, // where the ray hit, 0 if horizontal, 1 if vertical
+    pub exit_distance: f32, // distance between the player and where the ray leaves the hit cell
+    pub hit_side: i32, // where the ray hit, 0 if horizontal, 1 if vertical, -1 if it started in the cell
     pub pos_x: f32, // x position of ray hit
     pub pos_y: f32,
+    pub map_x: i32, // column of the hit cell
+    pub map_y: i32, // row of the hit cell
 }
 
 impl Ray{
@@ -64,273 +174,648 @@ impl Ray{
         Ray{
             angle: -1.0,
             distance: -1.0,
+            exit_distance: -1.0,
             hit_side: -1,
             pos_x: -1.0,
             pos_y: -1.0,
+            map_x: -1,
+            map_y: -1,
         }
     }
 
 }
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Game{
     pub player: Player,
-    pub wall_rays: [[Ray; RAY_COUNT]; 3],
-    pub floor_rays: [Ray; RAY_COUNT],
+    pub wall_rays: Vec<Vec<(usize, Ray)>>, // levels that the ray of every column_width pixels went through(closest first), from left to right
+    pub floor_rays: Vec<Ray>, // only the angles of the rays, floors and ceilings are cast row by row
     pub game_map: map::GameMap,
+    pub column_width: u32, // width of the screen column a ray is drawn to, 1 casts a ray for every pixel column
+    pub sprites: Vec<Sprite>,
+    pub entities: Vec<Entity>, // enemies, pickups and props, updated every tick
+    pub decals: Vec<hitscan::Decal>, // bullet holes on the walls, oldest first
+    pub player_radius: f32, // the player collides with the walls as a circle of this radius
+    pub spawn: Player, // the player respawns as this
+    pub hazards: Vec<damage::Hazard>,
+    pub arsenal: Arsenal, // weapons the player can hold
+    pub inventory: Inventory,
+    pub view_model: ViewModel, // walk bobbing of the held weapon
 
 }
 
+impl Game{
+    pub fn new(player: Player, game_map: map::GameMap) -> Game{
+        Game{
+            player,
+            wall_rays: Vec::new(),
+            floor_rays: Vec::new(),
+            game_map,
+            column_width: 1,
+            sprites: Vec::new(),
+            entities: Vec::new(),
+            decals: Vec::new(),
+            player_radius: PLAYER_RADIUS,
+            spawn: player,
+            hazards: Vec::new(),
+            arsenal: Arsenal::default(),
+            inventory: Inventory::new(&Arsenal::default()),
+            view_model: ViewModel::default(),
+        }
+    }
+
+    /// Gives the player the weapons of the arsenal, as they are after spawning
+    pub fn arm(&mut self, arsenal: Arsenal){
+        self.inventory = Inventory::new(&arsenal);
+        self.arsenal = arsenal;
+    }
+
+    /// Returns the camera of the player for a screen with the given size
+    pub fn camera(&self, screen_width: u32, screen_height: u32) -> Camera{
+        let projection = projection_distance(screen_width);
+        Camera{
+            projection,
+            // Looking up shears the picture down instead of rotating the camera, so walls stay vertical
+            horizon: (screen_height / 2) as f32 + self.player.pitch.to_radians().tan() * projection,
+            height: self.player.eye_height(),
+        }
+    }
+}
+
+/// How the world is projected to the screen
+#[derive(Debug, Copy, Clone)]
+pub struct Camera{
+    pub projection: f32, // distance of the projection plane
+    pub horizon: f32, // screen row of the eye level
+    pub height: f32, // height of the eyes from the floor
+}
+
 impl fmt::Display for Player {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "(POS[X={} | Y={}], Angle = {}, Dir[X={} | Y={}], Fired: {}", self.pos_x, self.pos_y, self.angle, self.dir_x, self.dir_y, self.fired)
+        write!(f, "(POS[X={} | Y={} | Z={}], Angle = {}, Dir[X={} | Y={}], Fired: {}", self.pos_x, self.pos_y, self.pos_z, self.angle, self.dir_x, self.dir_y, self.fired)
     }
 }
 
-/// Moves the player according to pressed keys(W/A/S/D)
-pub fn move_player(e: &sdl2::EventPump, game: &mut Game){
-    let pressed_keys:HashSet<Scancode> = e.keyboard_state().pressed_scancodes().collect();
-    if pressed_keys.contains(&Scancode::W){
-        game.player.pos_y += game.player.dir_y * PLAYER_SPEED;
-        game.player.pos_x += game.player.dir_x * PLAYER_SPEED;
-        if game.game_map.get_level(0, game.player.pos_x, game.player.pos_y) != 0{
-            game.player.pos_y -= game.player.dir_y * PLAYER_SPEED;
-            game.player.pos_x -= game.player.dir_x * PLAYER_SPEED;
+/// What the player wants to do in a frame, every axis is between -1 and 1
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Movement{
+    pub forward: f32, // 1 forward, -1 backward
+    pub strafe: f32, // 1 right, -1 left
+    pub turn: f32, // 1 left, -1 right
+    pub pitch: f32, // 1 up, -1 down
+    pub jump: bool,
+    pub crouch: bool,
+}
+
+impl Movement{
+    /// Returns the movement of the held actions, opposite actions cancel each other
+    pub fn from_actions(held: &HashSet<Action>) -> Movement{
+        let axis = |positive: Action, negative: Action| held.contains(&positive) as i32 as f32 - held.contains(&negative) as i32 as f32;
+        Movement{
+            forward: axis(Action::MoveForward, Action::MoveBackward),
+            strafe: axis(Action::StrafeRight, Action::StrafeLeft),
+            turn: axis(Action::TurnLeft, Action::TurnRight),
+            pitch: axis(Action::LookUp, Action::LookDown),
+            jump: held.contains(&Action::Jump),
+            crouch: held.contains(&Action::Crouch),
         }
     }
-    else if pressed_keys.contains(&Scancode::S){
-        game.player.pos_y -= game.player.dir_y * PLAYER_SPEED;
-        game.player.pos_x -= game.player.dir_x * PLAYER_SPEED;
-        if game.game_map.get_level(0, game.player.pos_x, game.player.pos_y) != 0{
-            game.player.pos_y += game.player.dir_y * PLAYER_SPEED;
-            game.player.pos_x += game.player.dir_x * PLAYER_SPEED;
+
+    /// Returns both movements done at once, the axes are added up to -1 and 1
+    pub fn combine(self, other: Movement) -> Movement{
+        let add = |a: f32, b: f32| (a + b).clamp(-1.0, 1.0);
+        Movement{
+            forward: add(self.forward, other.forward),
+            strafe: add(self.strafe, other.strafe),
+            turn: add(self.turn, other.turn),
+            pitch: add(self.pitch, other.pitch),
+            jump: self.jump || other.jump,
+            crouch: self.crouch || other.crouch,
         }
     }
-    if pressed_keys.contains(&Scancode::A){
-        game.player.angle += ROTATION_SPEED;
-        game.player.angle = normalize_angle(game.player.angle);
-        (game.player.dir_x, game.player.dir_y) = get_deltas(game.player.angle);
+}
+
+/// Returns the actions held down by the pressed keys, mouse buttons and controller buttons
+pub fn held_actions(e: &sdl2::EventPump, bindings: &Bindings, controllers: &Controllers) -> HashSet<Action>{
+    let keys = e.keyboard_state().pressed_scancodes().map(Binding::Key).collect::<Vec<Binding>>();
+    let buttons = e.mouse_state().pressed_mouse_buttons().map(Binding::Mouse).collect::<Vec<Binding>>();
+    bindings.held(keys.into_iter().chain(buttons).chain(controllers.held()))
+}
+
+/// Everything the player does in a tick of the game
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Input{
+    pub movement: Movement, // held keys, buttons and sticks
+    pub turn: f32, // degrees turned with the mouse since the last tick, positive turns left
+    pub look: f32, // degrees looked up(positive) or down with the mouse since the last tick
+    pub fire: bool, // fire is held or was pressed since the last tick
+    pub reload: bool, // reload was pressed since the last tick
+    pub select: Option<usize>, // index of the weapon picked with the number keys since the last tick
+    pub cycle: i32, // weapons to go forward(positive) or back with the mouse wheel
+}
+
+/// What happened in a tick that is heard or shown outside of the game, like sounds
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent{
+    Fired(usize), // index of the weapon in Game::arsenal
+    Hit(hitscan::Hit),
+    PickedUp(Entity),
+    Killed(Entity),
+    EnemyFired(Entity), // the enemy as it shot
+    Hurt(i32), // health the player lost
+    Died,
+    Respawned,
+    Reloading(usize), // index of the weapon
+    Switched(usize), // index of the weapon now held
+    OutOfAmmo, // fire was held without ammo for the weapon
+}
+
+/// Advances the game by a tick of delta_time seconds, the same inputs from the same game always give the same game
+pub fn update(game: &mut Game, input: &Input, delta_time: f32) -> Vec<GameEvent>{
+    let mut events: Vec<GameEvent> = Vec::new();
+    game.player.fired = false;
+    if let PlayerState::Dead{ .. } = game.player.state{
+        events.extend(update_dead(game, input, delta_time));
+        events.extend(entity::update_entities(game, delta_time));
+        return events;
+    }
+    game.player.turn(input.turn);
+    game.player.look(input.look);
+    update_player(game, &input.movement, delta_time);
+    events.extend(weapon::update_weapons(game, input, delta_time));
+    view_model::update_view_model(game, delta_time);
+    events.extend(damage::update_hazards(game, delta_time));
+    events.extend(entity::update_entities(game, delta_time));
+    events
+}
+
+/// Runs the death cam for a tick: the eyes sink to the floor and turn to where the killing damage came from.
+/// After RESPAWN_DELAY, fire or jump respawns the player as Game::spawn
+fn update_dead(game: &mut Game, input: &Input, delta_time: f32) -> Option<GameEvent>{
+    let PlayerState::Dead{ time, killer } = game.player.state else {return None;};
+    if time >= RESPAWN_DELAY && (input.fire || input.movement.jump){
+        game.player = game.spawn;
+        game.inventory = Inventory::new(&game.arsenal);
+        game.view_model = ViewModel::default();
+        return Some(GameEvent::Respawned);
+    }
+    game.player.state = PlayerState::Dead{ time: time + delta_time, killer };
+    game.player.camera_height = (game.player.camera_height - CROUCH_SPEED * delta_time).max(DEATH_CAMERA_HEIGHT);
+    if let Some((pos_x, pos_y)) = killer{
+        let player = &game.player;
+        let difference = (ai::angle_to(player.pos_x, player.pos_y, pos_x, pos_y) - player.angle + 180.0).rem_euclid(360.0) - 180.0;
+        let step = ROTATION_SPEED * delta_time;
+        game.player.turn(difference.clamp(-step, step));
+    }
+    // The body drops where it is
+    game.player.vel_x = 0.0;
+    game.player.vel_y = 0.0;
+    fall(game, delta_time);
+    None
+}
+
+/// Splits the time of the rendered frames into ticks of TICK_TIME
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Timestep{
+    accumulator: f32, // time that wasn't simulated yet, less than a tick after advance
+}
+
+impl Timestep{
+    /// Adds a frame of frame_time seconds and returns how many ticks to update, frames longer than MAX_FRAME_TIME are cut
+    pub fn advance(&mut self, frame_time: f32) -> u32{
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+        let ticks = (self.accumulator / TICK_TIME) as u32;
+        self.accumulator -= ticks as f32 * TICK_TIME;
+        ticks
+    }
+
+    /// Returns how far the time is between the last two ticks, for interpolating the rendered frame
+    pub fn alpha(&self) -> f32{
+        (self.accumulator / TICK_TIME).clamp(0.0, 1.0)
+    }
+}
 
+/// Turns, accelerates and moves the player for a frame of delta_time seconds, the result doesn't depend on the frame rate
+pub fn update_player(game: &mut Game, movement: &Movement, delta_time: f32){
+    let delta_time = delta_time.min(MAX_FRAME_TIME);
+    let turn = movement.turn.clamp(-1.0, 1.0) * ROTATION_SPEED * delta_time;
+    // Moving along the direction of the middle of the frame follows the same curve at every frame rate
+    let (dir_x, dir_y) = get_deltas(game.player.angle + turn / 2.0);
+    game.player.turn(turn);
+    game.player.look(movement.pitch.clamp(-1.0, 1.0) * PITCH_SPEED * delta_time);
+    crouch(game, movement.crouch, delta_time);
+    // Jumping only works from the ground, holding the key jumps again after landing
+    if movement.jump && on_ground(game){
+        game.player.vel_z = (2.0 * GRAVITY * JUMP_HEIGHT).sqrt();
     }
-    else if pressed_keys.contains(&Scancode::D){
-        game.player.angle -= ROTATION_SPEED;
-        game.player.angle = normalize_angle(game.player.angle);
-        (game.player.dir_x, game.player.dir_y) = get_deltas(game.player.angle);
+    // Right of the player is -90 degrees of the looking direction
+    let mut wish_x = dir_x * movement.forward - dir_y * movement.strafe;
+    let mut wish_y = dir_y * movement.forward + dir_x * movement.strafe;
+    // Diagonals aren't faster than moving straight
+    let length = (wish_x * wish_x + wish_y * wish_y).sqrt();
+    if length > 1.0{
+        wish_x /= length;
+        wish_y /= length;
     }
-    //println!("player {}", game.player);
+    let speed = if game.player.camera_height < CAMERA_HEIGHT {PLAYER_SPEED * CROUCH_MOVE_FACTOR} else {PLAYER_SPEED};
+    let (target_x, target_y) = (wish_x * speed, wish_y * speed);
+    // Velocity closes the gap to the target exponentially, moved distance is its exact integral over the frame
+    let rate = if length == 0.0 {FRICTION} else {ACCELERATION};
+    let decay = (-rate * delta_time).exp();
+    let step_x = target_x * delta_time + (game.player.vel_x - target_x) * (1.0 - decay) / rate;
+    let step_y = target_y * delta_time + (game.player.vel_y - target_y) * (1.0 - decay) / rate;
+    game.player.vel_x = target_x + (game.player.vel_x - target_x) * decay;
+    game.player.vel_y = target_y + (game.player.vel_y - target_y) * decay;
+    let (goal_x, goal_y) = (game.player.pos_x + step_x, game.player.pos_y + step_y);
+    try_move(game, goal_x, goal_y);
+    // Walls stop the velocity going into them
+    if game.player.pos_x != goal_x {game.player.vel_x = 0.0;}
+    if game.player.pos_y != goal_y {game.player.vel_y = 0.0;}
+    fall(game, delta_time);
 }
 
+/// Moves the player towards the position, X and Y are moved separately so the player slides along the walls.
+/// Returns false if the player couldn't move at all
+pub fn try_move(game: &mut Game, pos_x: f32, pos_y: f32) -> bool{
+    let start = (game.player.pos_x, game.player.pos_y);
+    step_to(game, pos_x, game.player.pos_y);
+    step_to(game, game.player.pos_x, pos_y);
+    (game.player.pos_x, game.player.pos_y) != start
+}
 
-// Draws the 2D world
-pub fn draw_2d_world(canvas: &mut Canvas<Window>, game: Game, gun_textures: &[Texture<'_>;3 ]){
+/// Moves the player to the position if every level and the entities leave room for them there
+fn step_to(game: &mut Game, pos_x: f32, pos_y: f32){
+    if entity::blocks(game, pos_x, pos_y) {return;}
+    if let Some(ground) = game.game_map.standing_height_around(pos_x, pos_y, game.player_radius, game.player.pos_z + STEP_HEIGHT, game.player.body_height()){
+        game.player.pos_x = pos_x;
+        game.player.pos_y = pos_y;
+        // Low ledges are stepped on, higher ground is left to fall
+        game.player.pos_z = game.player.pos_z.max(ground);
+    }
+}
+
+/// Returns true if the player stands on the floor or a block and isn't going up
+pub fn on_ground(game: &Game) -> bool{
+    game.player.vel_z <= 0.0 && game.game_map.standing_height_around(game.player.pos_x, game.player.pos_y, game.player_radius, game.player.pos_z, game.player.body_height())
+        == Some(game.player.pos_z)
+}
+
+/// Lowers the eyes of the player while crouch is held and raises them back when there is room above the head
+pub fn crouch(game: &mut Game, crouching: bool, delta_time: f32){
+    let step = CROUCH_SPEED * delta_time;
+    let player = &game.player;
+    let camera_height = if crouching {(player.camera_height - step).max(CROUCH_CAMERA_HEIGHT)} else {(player.camera_height + step).min(CAMERA_HEIGHT)};
+    let body_height = camera_height + PLAYER_HEIGHT - CAMERA_HEIGHT;
+    if camera_height < player.camera_height || game.game_map.fits_around(player.pos_x, player.pos_y, game.player_radius, player.pos_z, body_height){
+        game.player.camera_height = camera_height;
+    }
+}
+
+/// Moves the player up or down with gravity for delta_time seconds, until they land on what they stand on
+pub fn fall(game: &mut Game, delta_time: f32){
+    let player = &game.player;
+    let ground = game.game_map.standing_height_around(player.pos_x, player.pos_y, game.player_radius, player.pos_z, player.body_height())
+        .unwrap_or(player.pos_z);
+    // Height of the exact curve of the jump at the end of the frame, so it is the same at every frame rate
+    let pos_z = player.pos_z + player.vel_z * delta_time - GRAVITY * delta_time * delta_time / 2.0;
+    let vel_z = player.vel_z - GRAVITY * delta_time;
+    if pos_z <= ground{
+        game.player.pos_z = ground;
+        game.player.vel_z = 0.0;
+    }
+    // The head bumps into the levels above and the player starts falling
+    else if pos_z > player.pos_z && !game.game_map.fits_around(player.pos_x, player.pos_y, game.player_radius, pos_z, player.body_height()){
+        game.player.vel_z = 0.0;
+    }
+    else{
+        game.player.pos_z = pos_z;
+        game.player.vel_z = vel_z;
+    }
+}
+
+
+/// What frames draw between two ticks, the caller of render keeps the one of the previous tick
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Snapshot{
+    pub player: Player,
+    pub view_model: ViewModel,
+}
+
+impl Snapshot{
+    pub fn of(game: &Game) -> Snapshot{
+        Snapshot{ player: game.player, view_model: game.view_model }
+    }
+}
+
+/// What render keeps between frames, so the rays and buffers aren't allocated every frame
+#[derive(Debug, Clone)]
+pub struct RenderState{
+    view: Game, // the game as it is drawn, with the player and the held weapon between ticks
+    depth_buffer: Vec<f32>,
+}
+
+impl RenderState{
+    pub fn new(game: &Game) -> RenderState{
+        RenderState{ view: game.clone(), depth_buffer: Vec::new() }
+    }
+}
+
+/// Draws the frame the player sees, the player and the held weapon are drawn between the previous tick and the game at alpha.
+/// weapon_sheets has the sprite sheet of every weapon of Game::arsenal
+pub fn render<T: RenderTarget>(target: &mut T, state: &mut RenderState, game: &Game, previous: &Snapshot, alpha: f32, textures: &TextureSet, weapon_sheets: &[FrameBuffer]){
+    let (width, height) = target.size();
+    state.view.clone_from(game);
+    state.view.player = previous.player.lerp(&game.player, alpha);
+    state.view.view_model = previous.view_model.lerp(&game.view_model, alpha);
+    // Resets screen to black, if not hall of mirrors effect will be displayed
+    target.fill_rect(Rect::new(0, 0, width, height), BLACK);
+    get_rays(&mut state.view, width);
+    draw_rays(target, &state.view, textures, &mut state.depth_buffer);
+    // Entities are drawn like the sprites of the map
+    let mut sprites = state.view.sprites.clone();
+    sprites.extend(state.view.entities.iter().map(Entity::sprite));
+    draw_sprites(target, &state.view, &sprites, textures, &state.depth_buffer);
+    draw_2d_world(target, &state.view, weapon_sheets);
+}
+
+// Draws the minimap to the top right corner, screens too small for it don't get one
+fn draw_minimap<T: RenderTarget>(target: &mut T, game: &Game){
+    let (width, height) = target.size();
+    // Bigger maps get smaller blocks on the minimap
+    let minimap_block_size = (MINIMAP_SIZE / game.game_map.width.max(game.game_map.height) as u32).max(1);
+    let minimap_offset_x = width as i32 - (minimap_block_size as usize * game.game_map.width) as i32;
+    if minimap_offset_x < 0 || MINIMAP_OFFSET_Y + (minimap_block_size as usize * game.game_map.height) as i32 > height as i32 {return;}
     let mut x_position = 0;
     let mut y_position = 0;
-    canvas.set_draw_color(WHITE);
     for (_, row) in game.game_map.first_level.iter().enumerate() {
         for (_, value) in row.iter().enumerate() {
             if *value != 0{
-                canvas.set_draw_color(WHITE);
-                canvas.fill_rect(Rect::new(MINIMAP_OFFSET_X + x_position, MINIMAP_OFFSET_Y + y_position, MINIMAP_BLOCK_SIZE, MINIMAP_BLOCK_SIZE)).expect("Couldn't draw the block");
+                target.fill_rect(Rect::new(minimap_offset_x + x_position, MINIMAP_OFFSET_Y + y_position, minimap_block_size, minimap_block_size), WHITE);
             }
-            x_position += MINIMAP_BLOCK_SIZE as i32;
+            x_position += minimap_block_size as i32;
         }
-        y_position += MINIMAP_BLOCK_SIZE as i32;
+        y_position += minimap_block_size as i32;
         x_position = 0;
 
     }
     // Drawing the player to the minimap
-    let (player_minimap_x, player_minimap_y) = normalize_for_minimap(game.player.pos_x, game.player.pos_y);
-    canvas.set_draw_color(RED);
-    canvas.fill_rect(Rect::new(player_minimap_x + 2,
+    let (player_minimap_x, player_minimap_y) = normalize_for_minimap(minimap_offset_x, minimap_block_size, game.player.pos_x, game.player.pos_y);
+    target.fill_rect(Rect::new(player_minimap_x + 2,
                                player_minimap_y + 2,
-                                4, 4)).expect("Couldn't draw player");
-    // Drawing the gun
-    let gun = Rect::new(0, 0, 128, 184); // src
-    let position = Rect::new((WINDOW_WIDTH / 2) as i32 - 64, 512 - 184, 128, 184); // dst
-    if game.player.fired{
-        canvas.copy(&gun_textures[1], gun, position).expect("Couldn't draw the gun_fired");
+                                4, 4), RED);
+}
+
+// Draws the 2D world
+pub fn draw_2d_world<T: RenderTarget>(target: &mut T, game: &Game, weapon_sheets: &[FrameBuffer]){
+    let (width, height) = target.size();
+    draw_minimap(target, game);
+    // Health and armour bars, a pixel for every point
+    let health = game.player.health;
+    if health.health > 0{
+        target.fill_rect(Rect::new(8, height as i32 - 16, health.health as u32, 8), RED);
     }
-    else{
-        canvas.copy(&gun_textures[0], gun, position).expect("Couldn't draw the gun_normal");
+    if health.armour > 0{
+        target.fill_rect(Rect::new(8, height as i32 - 28, health.armour as u32, 8), BLUE);
+    }
+    // Rounds in the clip of the held weapon and the carried ammo of its type, a pixel for every round
+    let inventory = &game.inventory;
+    if let Some(ammo_type) = game.arsenal.weapons[inventory.current].ammo{
+        for round in 0..inventory.clips[inventory.current] as i32{
+            target.fill_rect(Rect::new(width as i32 - 12 - round * 5, height as i32 - 16, 3, 8), GREEN);
+        }
+        let carried = inventory.ammo[ammo_type];
+        if carried > 0{
+            target.fill_rect(Rect::new(width as i32 - 8 - carried as i32, height as i32 - 28, carried, 8), DARK_GREEN);
+        }
     }
+    // Dead players don't hold the gun
+    if game.player.state != PlayerState::Alive {return;}
+    view_model::draw_view_model(target, game, weapon_sheets);
 }
 
-// Draws the 2.5D world
-pub fn draw_rays(canvas: &mut Canvas<Window>, game: Game, wall_textures: &mut[Texture; 4], floor_textures: &mut[Texture; 1]){
-    canvas.set_scale(RAY_DRAWING_WIDTH as f32, 1f32).expect("Couldn't scale the canvas");
-
-    for (idx, level) in game.wall_rays.iter().rev().enumerate(){
-        let level_counter = 2 - idx;
-        let mut x_pos: i16 = 0;
-        for (_, ray) in level.iter().rev().enumerate(){
-            x_pos += 1;
-            if ray.pos_x == -1.0 || ray.pos_y == -1.0 {continue;}
-            // Calculations
-            let mut line_height: i32;
-            if ray.distance >= 1.0{
-                line_height = (BLOCKSIZE as i32 * WINDOW_HEIGHT as i32) / ray.distance as i32;
-            }
-            else{
-                line_height = WINDOW_HEIGHT as i32;
-            }
-            let y_step: f32 =  32 as f32 / (line_height as f32);
-
-            let mut y_offset: f32 = 0.0;
-            // In case the line height is bigger than screen normalize it
-            if line_height > WINDOW_HEIGHT as i32{
-                y_offset = line_height as f32 - (WINDOW_HEIGHT / 2) as f32;
-                line_height =  WINDOW_HEIGHT as i32;
+// Draws the 2.5D world, depth_buffer gets the distance of every pixel of the screen row by row
+pub fn draw_rays<T: RenderTarget>(target: &mut T, game: &Game, textures: &TextureSet, depth_buffer: &mut Vec<f32>){
+    let (width, height) = target.size();
+    let camera = game.camera(width, height);
+    depth_buffer.clear();
+    depth_buffer.resize((width * height) as usize, f32::INFINITY);
+    // Every pixel of the column keeps the distance of what is drawn on it, so only the closest thing stays visible
+    let mut column: Vec<Color> = vec![BLACK; height as usize];
+    let mut depth: Vec<f32> = vec![f32::INFINITY; height as usize];
+    for (column_idx, floor_ray) in game.floor_rays.iter().enumerate(){
+        column.fill(BLACK);
+        depth.fill(f32::INFINITY);
+        draw_floor_column(&mut column, &mut depth, game, textures, floor_ray.angle, &camera);
+        for (level_idx, ray) in &game.wall_rays[column_idx]{
+            draw_wall_column(&mut column, &mut depth, game, textures, *level_idx, *ray, &camera);
+        }
+        // Every ray is column_width pixels wide
+        for x_offset in 0..game.column_width as i32{
+            let x = column_idx as i32 * game.column_width as i32 + x_offset;
+            if x >= width as i32 {break;}
+            target.draw_column(x, 0, &column);
+            for (row, distance) in depth.iter().enumerate(){
+                depth_buffer[row * width as usize + x as usize] = *distance;
             }
+        }
+    }
 
-            let mut line_start: i32 = (-line_height / 2) + (WINDOW_HEIGHT / 2) as i32;
-
-            let mut x: f32 = 0.0;
-            let mut y: f32 = y_step * y_offset;
-            let mut shade = 1.0;
-            if ray.hit_side == 1{
-                shade = 0.7;
-                x = (ray.pos_y / 2.0) % 32.0;
-                if ray.angle > 180.0{
-                    x = 31.0 - x;
-                }
-            }
-            else if ray.hit_side == 0{
-                x = (ray.pos_x / 2.0) % 32.0;
-                if ray.angle > 90.0 && ray.angle < 270.0{
-                    x = 31.0 - x;
-                }
-            }
-            //println!("line_height {}, line_start: {}",line_height, line_start);
-
-            // Drawing
-            for y_pos in 0..line_height as i32{
-                let pixel = ((y as i32 * 32 + x as i32) * 3) as usize;
-                //println!("x: {x} - y: {y}, pixel: {pixel}");
-                let red = TEXTURE_1[pixel] as f32 * shade;
-                let green = TEXTURE_1[pixel + 1] as f32 * shade;
-                let blue = TEXTURE_1[pixel + 2] as f32* shade;
-
-                canvas.set_draw_color(Color::RGB(red as u8, green as u8, blue as u8));
-                let point = Point::new(x_pos as i32, y_pos + line_start as i32);
-                canvas.draw_point(point).expect("Couldn't draw point");
-                y += y_step;
-                y = y % 32 as f32;
+}
 
-            }
+/// Draws the wall slice of the level that the ray hit, and its top or bottom if the eyes are above or below it
+fn draw_wall_column(column: &mut [Color], depth: &mut [f32], game: &Game, textures: &TextureSet, level_idx: usize, ray: Ray, camera: &Camera){
+    let (map_x, map_y) = (ray.map_x as usize, ray.map_y as usize);
+    let tile = game.game_map.get_cell(level_idx, map_x, map_y);
+    let wall_texture = textures.wall(tile);
+    // Levels are stacked on top of each other, every level is a block high
+    let bottom = level_idx as f32 * BLOCKSIZE as f32;
+    let top = bottom + BLOCKSIZE as f32;
+    if camera.height > top && (level_idx + 1 >= 3 || game.game_map.get_cell(level_idx + 1, map_x, map_y) == 0){
+        draw_cap_column(column, depth, game, &wall_texture.light, top, ray, camera);
+    }
+    if camera.height < bottom && level_idx > 0 && game.game_map.get_cell(level_idx - 1, map_x, map_y) == 0{
+        draw_cap_column(column, depth, game, &wall_texture.light, bottom, ray, camera);
+    }
+    if ray.hit_side == -1 {return;}
+    let texture = if ray.hit_side == 1 {&wall_texture.dark} else {&wall_texture.light};
+    // Where the ray hit the wall, between 0 and 1
+    let mut wall_x: f32 = 0.0;
+    if ray.hit_side == 1{
+        wall_x = (ray.pos_y % BLOCKSIZE as f32) / BLOCKSIZE as f32;
+        if ray.angle > 180.0{
+            wall_x = 1.0 - wall_x;
+        }
+    }
+    else if ray.hit_side == 0{
+        wall_x = (ray.pos_x % BLOCKSIZE as f32) / BLOCKSIZE as f32;
+        if ray.angle > 90.0 && ray.angle < 270.0{
+            wall_x = 1.0 - wall_x;
         }
     }
+    let x = ((wall_x * texture.width as f32) as u32).min(texture.width - 1);
+    let distance = ray.distance.max(1.0);
+    let line_top = camera.horizon + (camera.height - top) * camera.projection / distance;
+    let line_bottom = camera.horizon + (camera.height - bottom) * camera.projection / distance;
+    let y_step = texture.height as f32 / (line_bottom - line_top);
+    let first_row = line_top.max(0.0) as usize;
+    let last_row = (line_bottom.max(0.0) as usize).min(column.len());
+    // Bullet holes on this slice of the wall
+    let decals: Vec<(f32, f32)> = game.decals.iter().filter_map(|decal| decal.offset(&ray).map(|offset| (offset, decal.height))).collect();
+    for row in first_row..last_row{
+        if depth[row] <= distance {continue;}
+        let y = (((row as f32 + 0.5 - line_top) * y_step) as u32).min(texture.height - 1);
+        column[row] = texture.get_pixel(x, y);
+        depth[row] = distance;
+        if decals.is_empty() {continue;}
+        let row_height = camera.height - (row as f32 + 0.5 - camera.horizon) * distance / camera.projection;
+        if decals.iter().any(|(offset, height)| offset * offset + (row_height - height) * (row_height - height) <= hitscan::DECAL_RADIUS * hitscan::DECAL_RADIUS){
+            let color = column[row];
+            column[row] = Color::RGBA(color.r / 4, color.g / 4, color.b / 4, color.a);
+        }
+    }
+}
+
+/// Draws the flat side of a level at the given height, where the ray goes over or under the cell
+fn draw_cap_column(column: &mut [Color], depth: &mut [f32], game: &Game, texture: &FrameBuffer, plane_height: f32, ray: Ray, camera: &Camera){
+    let (dir_x, dir_y) = get_deltas(ray.angle);
+    let fisheye = (game.player.angle - ray.angle).to_radians().cos();
+    // Rows of where the ray enters and leaves the cell, the cell the ray starts in reaches the edge of the screen
+    let height_offset = camera.height - plane_height;
+    let entry_row = camera.horizon + height_offset * camera.projection / ray.distance;
+    let exit_row = camera.horizon + height_offset * camera.projection / ray.exit_distance;
+    let first_row = entry_row.min(exit_row).max(0.0) as usize;
+    let last_row = (entry_row.max(exit_row).max(0.0) as usize).min(column.len());
+    for row in first_row..last_row{
+        let distance = height_offset * camera.projection / (row as f32 + 0.5 - camera.horizon);
+        if depth[row] <= distance {continue;}
+        let pos_x = game.player.pos_x + dir_x * distance / fisheye;
+        let pos_y = game.player.pos_y + dir_y * distance / fisheye;
+        let x = (pos_x.rem_euclid(BLOCKSIZE as f32) / BLOCKSIZE as f32 * texture.width as f32) as u32;
+        let y = (pos_y.rem_euclid(BLOCKSIZE as f32) / BLOCKSIZE as f32 * texture.height as f32) as u32;
+        column[row] = texture.get_pixel(x.min(texture.width - 1), y.min(texture.height - 1));
+        depth[row] = distance;
+    }
+}
 
+/// Casts the floor below and the ceiling above the horizon for a column, cells without a ceiling are left to the sky
+fn draw_floor_column(column: &mut [Color], depth: &mut [f32], game: &Game, textures: &TextureSet, ray_angle: f32, camera: &Camera){
+    let (dir_x, dir_y) = get_deltas(ray_angle);
+    // Distances of the rows are perpendicular to the screen like the walls, the ray itself is longer
+    let fisheye = (game.player.angle - ray_angle).to_radians().cos();
+    for (row, pixel) in column.iter_mut().enumerate(){
+        let row_offset = row as f32 + 0.5 - camera.horizon;
+        let is_floor = row_offset > 0.0;
+        let plane_height = if is_floor {camera.height} else {CEILING_HEIGHT - camera.height};
+        // Ceilings below the eyes aren't seen from under them
+        if plane_height <= 0.0 {continue;}
+        let distance = plane_height * camera.projection / row_offset.abs();
+        let pos_x = game.player.pos_x + dir_x * distance / fisheye;
+        let pos_y = game.player.pos_y + dir_y * distance / fisheye;
+        if out_of_index(&game.game_map, pos_x, pos_y) {continue;}
+        let tile = if is_floor {game.game_map.get_floor(pos_x, pos_y)} else {game.game_map.get_ceiling(pos_x, pos_y)};
+        if let Some(texture) = textures.floor(tile){
+            let x = ((pos_x % BLOCKSIZE as f32) / BLOCKSIZE as f32 * texture.width as f32) as u32;
+            let y = ((pos_y % BLOCKSIZE as f32) / BLOCKSIZE as f32 * texture.height as f32) as u32;
+            *pixel = texture.get_pixel(x.min(texture.width - 1), y.min(texture.height - 1));
+            depth[row] = distance;
+        }
+    }
 }
 
 
-/// Casts rays and returns the ray distance(s) and the side(s) they were hit
-pub fn get_rays(game: &mut Game, canvas: &mut Canvas<Window>){
-    let mut rays: [[Ray; RAY_COUNT]; 3] = [[Ray::new(); RAY_COUNT], [Ray::new(); RAY_COUNT], [Ray::new(); RAY_COUNT]]; 
-    let player_x = game.player.pos_x;
-    let player_y = game.player.pos_y;
+/// Casts a ray for every column_width pixels of a screen with the given width and stores the ray distance(s) and the side(s) they were hit
+pub fn get_rays(game: &mut Game, screen_width: u32){
+    let ray_count = screen_width.div_ceil(game.column_width) as usize;
     let player_angle = game.player.angle;
-    // ** //
-    let mut current_x: f32;
-    let mut current_y: f32;
-    let ray_step: f32 = 0.75;
-    let mut ray_angle: f32 = game.player.angle - (RAY_COUNT as f32 / 2.0 * ray_step);
-    let mut array_idx: usize = 0;
-    // For debug purposes
-    if RAY_COUNT == 1{
-        ray_angle = player_angle;
-    }
-    loop {
-        ray_angle = normalize_angle(ray_angle);
-        let mut x_step: f32;
-        let mut y_step: f32;
-        let inverse_tan = 1.0/(ray_angle.to_radians().tan());
-        let tan = (ray_angle).to_radians().tan();
-
-        // Horizontal Check //
-        if ray_angle > 0.0 && ray_angle < 180.0 { // facing up
-            y_step = -(BLOCKSIZE as f32);
-            current_y = ((player_y as i32 / BLOCKSIZE as i32) as f32 * BLOCKSIZE as f32) - 0.001;
-        }
-        else { // facing down, if ray_angle > 180.0 && ray_angle < 360.0 
-            y_step = BLOCKSIZE as f32;
-            current_y = ((player_y as i32 / BLOCKSIZE as i32) as f32 * BLOCKSIZE as f32) + BLOCKSIZE as f32;
-        }
-        x_step = -y_step * inverse_tan;
-        current_x = (player_y - current_y) * inverse_tan + player_x;
-        let (horizontal_distances, horizontal_hit_poses) = calculate_distances(game.game_map, current_x, current_y, x_step, y_step, ray_angle, player_x, player_y, false);
-            
-        // Horizontal Check end //
-
-        // Vertical Check //
-        if ray_angle > 90.0 && ray_angle < 270.0  { // facing left
-            x_step = -(BLOCKSIZE as f32);
-            current_x = ((player_x as i32 / BLOCKSIZE as i32) as f32 * BLOCKSIZE as f32) - 0.001;
-        }
-        else if ray_angle > 270.0 || ray_angle < 90.0{ // facing right
-            x_step = BLOCKSIZE as f32; 
-            current_x = ((player_x as i32 / BLOCKSIZE as i32) as f32 * BLOCKSIZE as f32) + BLOCKSIZE as f32;
-        }
-        y_step = -x_step * tan;
-        current_y = (player_x - current_x) * tan + player_y;
-
-        let (vertical_distances, vertical_hit_poses) = calculate_distances(game.game_map, current_x, current_y, x_step, y_step, ray_angle, player_x, player_y, true);
-        // Vertical Check end //
-        let mut current_ray = Ray::new();
-        current_ray.angle = ray_angle;
-        for (idx, _) in horizontal_distances.iter().enumerate(){
-            if horizontal_distances[idx] < vertical_distances[idx]{
-                current_ray.distance = fix_fisheye(player_angle, ray_angle, horizontal_distances[idx]);
-                current_ray.hit_side = 0;
-                current_ray.pos_x = horizontal_hit_poses[idx].0;
-                current_ray.pos_y = horizontal_hit_poses[idx].1;
-            }
-            else{
-                current_ray.distance = fix_fisheye(player_angle, ray_angle, vertical_distances[idx]);
-                current_ray.hit_side = 1;
-                current_ray.pos_x = vertical_hit_poses[idx].0;
-                current_ray.pos_y = vertical_hit_poses[idx].1;
-            }
-            rays[idx][array_idx] = current_ray;
+    let projection = projection_distance(screen_width);
+    game.wall_rays.clear();
+    game.wall_rays.resize(ray_count, Vec::new());
+    game.floor_rays.clear();
+    game.floor_rays.resize(ray_count, Ray::new());
+    for array_idx in 0..ray_count{
+        // Rays go through equally spaced points of the screen, not equal angles, so walls don't bend
+        let screen_x = screen_width as f32 / 2.0 - (array_idx as f32 + 0.5) * game.column_width as f32;
+        let ray_angle = normalize_angle(player_angle + screen_x.atan2(projection).to_degrees());
+        game.floor_rays[array_idx].angle = ray_angle;
+        let mut hits = cast_ray_through(&game.game_map, game.player.pos_x, game.player.pos_y, ray_angle);
+        for (_, hit) in hits.iter_mut(){
+            hit.distance = fix_fisheye(player_angle, ray_angle, hit.distance);
+            hit.exit_distance = fix_fisheye(player_angle, ray_angle, hit.exit_distance);
         }
-        ray_angle += ray_step;
-        array_idx += 1;
+        game.wall_rays[array_idx] = hits;
+    }
+}
 
-        
-        if array_idx == RAY_COUNT{
-            game.wall_rays = rays;
-            break;
-        }
-        }
+/// Distance of the projection plane for the given screen width in pixels
+pub fn projection_distance(screen_width: u32) -> f32{
+    (screen_width as f32 / 2.0) / (FOV / 2.0).to_radians().tan()
 }
 
+/// Returns the first hit of every level along the ray,
+/// levels that aren't hit are left as Ray::new()
+pub fn cast_ray(game_map: &map::GameMap, start_x: f32, start_y: f32, ray_angle: f32) -> [Ray; 3]{
+    let mut hits = [Ray::new(); 3];
+    for (idx, hit) in cast_ray_through(game_map, start_x, start_y, ray_angle){
+        if hit.hit_side != -1 && hits[idx].hit_side == -1{
+            hits[idx] = hit;
+        }
+    }
+    hits
+}
 
-pub fn fire(game: Game) -> Vec<Rect>{
-    let mut bullets: Vec<Rect> = Vec::new();
-    let mut bullet_x = game.player.pos_x;
-    let mut bullet_y = game.player.pos_y;
-    let mut drawing_x_pos = 330;
-    let mut drawing_y_pos = 330;
-    let mut height: i32 = 64;
-    let mut width: i32 = 64;
-    loop {
-        if {(bullet_x > WORLDSIZE as f32 || bullet_y > WORLDSIZE as f32
-            || (bullet_x < 0.0 || bullet_y < 0.0))
-            || game.game_map.get_level(0, bullet_x, bullet_y) != 0} 
-        {
-            bullets.reverse();
-            return bullets;
+/// Walks the grid cell by cell along the ray(DDA) until it leaves the map and returns every level it went through with their index,
+/// closest first. Levels of the cell the ray starts in have hit_side -1 and 0 distance
+pub fn cast_ray_through(game_map: &map::GameMap, start_x: f32, start_y: f32, ray_angle: f32) -> Vec<(usize, Ray)>{
+    let mut hits: Vec<(usize, Ray)> = Vec::new();
+    let (dir_x, dir_y) = get_deltas(ray_angle);
+    let block = BLOCKSIZE as f32;
+    let mut map_x = (start_x / block).floor() as i32;
+    let mut map_y = (start_y / block).floor() as i32;
+    // Distance along the ray to cross one cell, infinite if the ray is parallel to that axis
+    let delta_x = (block / dir_x).abs();
+    let delta_y = (block / dir_y).abs();
+    let step_x = if dir_x < 0.0 {-1} else {1};
+    let step_y = if dir_y < 0.0 {-1} else {1};
+    // Distance along the ray to the first vertical and horizontal grid line
+    let mut side_x = if dir_x < 0.0 {start_x - map_x as f32 * block} else {(map_x + 1) as f32 * block - start_x} / dir_x.abs();
+    let mut side_y = if dir_y < 0.0 {start_y - map_y as f32 * block} else {(map_y + 1) as f32 * block - start_y} / dir_y.abs();
+    let mut distance: f32 = 0.0;
+    let mut hit_side: i32 = -1;
+    while !out_of_index(game_map, map_x as f32 * block, map_y as f32 * block){
+        let exit_distance = side_x.min(side_y);
+        for idx in 0..3{
+            if game_map.get_cell(idx, map_x as usize, map_y as usize) == 0 {continue;}
+            hits.push((idx, Ray{
+                angle: ray_angle,
+                distance,
+                exit_distance,
+                hit_side,
+                pos_x: start_x + dir_x * distance,
+                pos_y: start_y + dir_y * distance,
+                map_x,
+                map_y,
+            }));
+        }
+        if side_x < side_y{
+            side_x += delta_x;
+            map_x += step_x;
+            hit_side = 1;
         }
         else{
-            let position = Rect::new(drawing_x_pos, drawing_y_pos, width as u32, height as u32); // dst
-            bullets.push(position);
+            side_y += delta_y;
+            map_y += step_y;
+            hit_side = 0;
         }
-        drawing_x_pos += 2;
-        width -= 5;
-        height -= 5;
-        drawing_y_pos -= 5;
-        bullet_x += game.player.dir_x * BULLET_SPEED;
-        bullet_y += game.player.dir_y * BULLET_SPEED;
+        distance = exit_distance;
     }
+    hits
+}
+
 
+/// Shoots from the player to the angle, leaves a bullet hole if a wall was hit and returns what was hit
+pub fn fire(game: &mut Game, angle: f32) -> Option<hitscan::Hit>{
+    let hit = hitscan::hitscan(game, angle)?;
+    hitscan::add_decal(game, &hit);
+    Some(hit)
 }
 
 
@@ -340,11 +825,6 @@ fn fix_fisheye(player_angle: f32, current_angle: f32, distance: f32) -> f32{
     return distance * (angle_difference.to_radians().cos());
 }
 
-/// Returns distance of two points
-fn get_distance(player_x: f32, player_y: f32, current_x: f32, current_y: f32, ray_angle: f32) -> f32{
-    return (ray_angle).to_radians().cos() * (current_x - player_x)-((ray_angle).to_radians().sin())*(current_y-player_y);
-}
-
 /// Normalizes angle of the player to 0 <= angle < 360
 fn normalize_angle(angle: f32) -> f32{
     if angle < 0.0{
@@ -365,11 +845,11 @@ pub fn get_deltas(angle: f32) -> (f32, f32){
     return (delta_x, delta_y);
 }
 
-/// Gets x and y position of a point with sizes of the array, returns 1 if they are out of index
-fn out_of_index(x_position: f32, y_position: f32) -> bool{
+/// Gets x and y position of a point with sizes of the array, returns true if they are out of index
+fn out_of_index(game_map: &map::GameMap, x_position: f32, y_position: f32) -> bool{
     let idx_y: usize = x_position as usize / BLOCKSIZE as usize; // THESE TWO ARE CORRECT
     let idx_x: usize = y_position as usize / BLOCKSIZE as usize; // DUE TO HOW SDL2 HANDLES X/Y AXIS'
-    if idx_y >= MAP_LENGTH || idx_x >= MAP_WIDTH 
+    if idx_y >= game_map.width || idx_x >= game_map.height 
        || x_position < 0.0 || y_position < 0.0{
         return true;
     }
@@ -380,41 +860,13 @@ fn out_of_index(x_position: f32, y_position: f32) -> bool{
 }
 
 /// Normalizes X and Y position relative to scale of minimap
-fn normalize_for_minimap(pos_x: f32, pos_y: f32) -> (i32, i32){
+fn normalize_for_minimap(minimap_offset_x: i32, minimap_block_size: u32, pos_x: f32, pos_y: f32) -> (i32, i32){
+    let scale = BLOCKSIZE as f32 / minimap_block_size as f32;
     return (
-    MINIMAP_OFFSET_X + (pos_x / (MINIMAP_BLOCK_SIZE ) as f32) as i32,
-    MINIMAP_OFFSET_Y +(pos_y / (MINIMAP_BLOCK_SIZE ) as f32) as i32);
-}
-
-/// Calculates distances of each level, if it doesn't hit an array returns distance of 9999 and hit poses of (-1.0, -1.0)
-fn calculate_distances(game_map: map::GameMap, orig_current_x: f32, orig_current_y: f32, x_step: f32, y_step: f32, ray_angle: f32, player_x: f32, player_y: f32, vertical: bool) -> ([f32; 3], [(f32, f32); 3]) {
-    let mut distances: [f32; 3] = [9999.0; 3];
-    let mut hit_poses: [(f32, f32); 3] = [(-1.0, -1.0); 3];
-    for idx in 0..3{
-        let mut current_x = orig_current_x;
-        let mut current_y = orig_current_y;
-        'inner: loop{
-            if vertical{
-                if ray_angle == 90.0 || ray_angle == 270.0 {break 'inner};
-            }
-            if !vertical{
-                if ray_angle == 180.0 || normalize_angle(ray_angle) == 0.0 {break 'inner};
-            }
-            // println!("Vertical: {}, Level: {}, ray_angle: {}, current_x: {}, current_y: {}, x_step: {}, y_step {}", vertical, idx, ray_angle, current_x, current_y, x_step, y_step);
-            if out_of_index(current_x, current_y) {hit_poses[idx] = (-1.0, -1.0); break 'inner};
-            if game_map.get_level(idx as i32, current_x, current_y) != 0{
-                distances[idx] = get_distance(player_x, player_y, current_x, current_y, ray_angle);
-                hit_poses[idx] = (current_x, current_y);//((current_x as u32 / BLOCKSIZE) as i32, (current_y as u32 / BLOCKSIZE) as i32);
-                break 'inner;
-            }
-            current_x += x_step;
-            current_y += y_step;
-        }
-    }
-    return (distances, hit_poses);
+    minimap_offset_x + (pos_x / scale) as i32,
+    MINIMAP_OFFSET_Y +(pos_y / scale) as i32);
 }
 
-
 /// Tests
 #[cfg(test)]
 mod tests {
@@ -442,11 +894,296 @@ mod tests {
     }
     #[test]
     fn test_out_of_index() {
-        assert_eq!(out_of_index(0_f32, 0_f32), false);
-        assert_eq!(out_of_index(64_f32, 64_f32), false);
-        assert_eq!(out_of_index(513_f32, 513_f32), true);
-        assert_eq!(out_of_index(1000_f32, 70_f32), true);
-        assert_eq!(out_of_index(70_f32, 1000_f32), true);
+        let game_map = map::load_map(map::DEFAULT_MAP).unwrap().game_map;
+        assert_eq!(out_of_index(&game_map, 0_f32, 0_f32), false);
+        assert_eq!(out_of_index(&game_map, 64_f32, 64_f32), false);
+        let (width, height) = ((game_map.width * BLOCKSIZE as usize) as f32, (game_map.height * BLOCKSIZE as usize) as f32);
+        assert_eq!(out_of_index(&game_map, 1000_f32, 1000_f32), false);
+        assert_eq!(out_of_index(&game_map, width, 70_f32), true);
+        assert_eq!(out_of_index(&game_map, 70_f32, height), true);
+    }
+    #[test]
+    fn test_cast_ray_axis_aligned() {
+        // (300, 300) is in the 5th column and row, surrounded by the outer walls
+        let game_map = map::load_map(map::DEFAULT_MAP).unwrap().game_map;
+        let expected = [(0.0, 660.0, 1, (15, 4)), (90.0, 236.0, 0, (4, 0)), (180.0, 236.0, 1, (0, 4)), (270.0, 660.0, 0, (4, 15))];
+        for (angle, distance, side, (map_x, map_y)) in expected{
+            let hits = cast_ray(&game_map, 300.0, 300.0, angle);
+            assert_eq!(hits[0].distance, distance);
+            assert_eq!(hits[0].hit_side, side);
+            assert_eq!((hits[0].map_x, hits[0].map_y), (map_x, map_y));
+            // Outer walls only have the first level
+            assert_eq!(hits[1].hit_side, -1);
+            assert_eq!(hits[2].hit_side, -1);
+        }
+    }
+    #[test]
+    fn test_cast_ray_through() {
+        // Starting on the outer wall, the ray goes through the wall it starts in and leaves the map on the other side
+        let game_map = map::load_map(map::DEFAULT_MAP).unwrap().game_map;
+        let hits = cast_ray_through(&game_map, 32.0, 300.0, 0.0);
+        let (level, first) = hits[0];
+        assert_eq!((level, first.hit_side, first.distance, first.exit_distance), (0, -1, 0.0, 32.0));
+        let (level, last) = hits[hits.len() - 1];
+        assert_eq!((level, last.hit_side, last.map_x, last.distance, last.exit_distance), (0, 1, 15, 928.0, 992.0));
+    }
+    #[test]
+    fn test_try_move_and_fall() {
+        // An empty cell, a ledge of one level and an overhang on the second level
+        let mut game_map = map::GameMap::new(3, 1);
+        game_map.first_level[0][1] = 1;
+        game_map.second_level[0][2] = 1;
+        let player = Player::new(32.0, 32.0, 0.0);
+        let mut game = Game::new(player, game_map);
+        assert!(!try_move(&mut game, 96.0, 32.0));
+        assert!(try_move(&mut game, 160.0, 32.0));
+        assert_eq!((game.player.pos_x, game.player.pos_z), (160.0, 0.0));
+        // Walking off the top of the ledge drops the player to the floor
+        game.player.pos_x = 96.0;
+        game.player.pos_z = BLOCKSIZE as f32;
+        fall(&mut game, 0.0625);
+        assert_eq!(game.player.pos_z, BLOCKSIZE as f32);
+        assert!(try_move(&mut game, 32.0, 32.0));
+        fall(&mut game, 0.0625);
+        assert_eq!(game.player.pos_z, BLOCKSIZE as f32 - GRAVITY * 0.0625 * 0.0625 / 2.0);
+        fall(&mut game, 1.0);
+        assert_eq!(game.player.pos_z, 0.0);
+    }
+    #[test]
+    fn test_try_move_slides() {
+        // A room of one cell with walls on the top row
+        let mut game_map = map::GameMap::new(3, 3);
+        game_map.first_level[0] = vec![1, 1, 1];
+        let player = Player::new(96.0, 96.0, 0.0);
+        let mut game = Game::new(player, game_map);
+        // Moving diagonally into the wall keeps the move along it
+        assert!(try_move(&mut game, 100.0, 76.0));
+        assert_eq!((game.player.pos_x, game.player.pos_y), (100.0, 96.0));
+        assert!(try_move(&mut game, 104.0, 80.0));
+        assert_eq!((game.player.pos_x, game.player.pos_y), (104.0, 80.0));
+        // Into the corner of the wall and the edge of the map, both axes are blocked
+        game.player.pos_x = 176.0;
+        assert!(!try_move(&mut game, 180.0, 76.0));
+        assert_eq!((game.player.pos_x, game.player.pos_y), (176.0, 80.0));
+        // A bigger player doesn't fit where it stands
+        game.player_radius = 20.0;
+        assert!(!try_move(&mut game, 176.0, 80.0));
+    }
+    /// Runs a second of the movement at the frame rate, from the spawn of the default map
+    fn move_for_a_second(movement: Movement, frame_rate: u32) -> Player{
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        for _ in 0..frame_rate{
+            update_player(&mut game, &movement, 1.0 / frame_rate as f32);
+        }
+        game.player
+    }
+    #[test]
+    fn test_update_player_frame_rate() {
+        let movement = Movement{ forward: 1.0, turn: 0.3, ..Movement::default() };
+        let at_60 = move_for_a_second(movement, 60);
+        for frame_rate in [30, 144]{
+            let player = move_for_a_second(movement, frame_rate);
+            assert!((player.angle - at_60.angle).abs() < 0.01);
+            // The path curves, so other frame rates are only nearly the same
+            assert!((player.pos_x - at_60.pos_x).abs() < 0.1 && (player.pos_y - at_60.pos_y).abs() < 0.1);
+        }
+        // Straight moves are exact
+        let straight = Movement{ forward: 1.0, ..Movement::default() };
+        assert!((move_for_a_second(straight, 30).pos_x - move_for_a_second(straight, 144).pos_x).abs() < 0.01);
+    }
+    #[test]
+    fn test_update_player_strafe() {
+        // Looking to +X, strafing right goes to +Y and diagonals are as fast as straight moves
+        let strafe = move_for_a_second(Movement{ strafe: 1.0, ..Movement::default() }, 60);
+        assert!((strafe.pos_x - 300.0).abs() < 0.01 && strafe.pos_y > 400.0);
+        let diagonal = move_for_a_second(Movement{ forward: 1.0, strafe: -1.0, ..Movement::default() }, 60);
+        let distance = ((diagonal.pos_x - 300.0).powi(2) + (diagonal.pos_y - 300.0).powi(2)).sqrt();
+        assert!((distance - (strafe.pos_y - 300.0)).abs() < 0.01);
+        assert!(diagonal.pos_y < 300.0);
+        // Friction stops the player after the keys are released
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        update_player(&mut game, &Movement{ forward: 1.0, ..Movement::default() }, 0.1);
+        for _ in 0..60{
+            update_player(&mut game, &Movement::default(), 1.0 / 60.0);
+        }
+        assert!(game.player.vel_x.abs() < 5.0);
+    }
+    #[test]
+    fn test_movement_from_actions() {
+        let movement = Movement::from_actions(&HashSet::from([Action::MoveForward, Action::StrafeLeft, Action::TurnLeft, Action::TurnRight, Action::Jump]));
+        assert_eq!(movement, Movement{ forward: 1.0, strafe: -1.0, jump: true, ..Movement::default() });
+        assert_eq!(Movement::from_actions(&HashSet::from([Action::Fire, Action::Pause])), Movement::default());
+        // Keys and a stick pushed the same way aren't faster than one of them
+        let stick = Movement{ forward: 0.5, strafe: 0.5, turn: -0.25, crouch: true, ..Movement::default() };
+        assert_eq!(movement.combine(stick), Movement{ forward: 1.0, strafe: -0.5, turn: -0.25, jump: true, crouch: true, ..Movement::default() });
+    }
+    #[test]
+    fn test_timestep() {
+        let mut timestep = Timestep::default();
+        // Frames faster than the ticks update the game every few frames
+        let ticks: u32 = (0..24).map(|_| timestep.advance(TICK_TIME / 4.0)).sum();
+        assert_eq!(ticks, 6);
+        assert!(timestep.alpha() < 0.01 || timestep.alpha() > 0.99);
+        assert_eq!(timestep.advance(TICK_TIME * 2.5), 2);
+        assert!((timestep.alpha() - 0.5).abs() < 0.01);
+        // A long frame isn't caught up all at once
+        assert_eq!(timestep.advance(10.0), (MAX_FRAME_TIME / TICK_TIME) as u32);
+    }
+    /// Runs the held movement for a second of frames at the frame rate, returns the player after every tick
+    fn play_for_a_second(frame_rate: u32) -> Vec<Player>{
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        let mut timestep = Timestep::default();
+        let input = Input{ movement: Movement{ forward: 1.0, turn: 0.3, ..Movement::default() }, ..Input::default() };
+        let mut ticks: Vec<Player> = Vec::new();
+        for _ in 0..frame_rate{
+            for _ in 0..timestep.advance(1.0 / frame_rate as f32){
+                update(&mut game, &input, TICK_TIME);
+                ticks.push(game.player);
+            }
+        }
+        ticks
+    }
+    #[test]
+    fn test_update_rendering_speed() {
+        // Every tick is the same however fast the frames are
+        let ticks = play_for_a_second(144);
+        for frame_rate in [30, 60, 75]{
+            let other = play_for_a_second(frame_rate);
+            assert!(other.len().abs_diff(TICK_RATE as usize) <= 1);
+            assert!(other.iter().zip(&ticks).all(|(a, b)| a == b));
+        }
+    }
+    #[test]
+    fn test_update_fire() {
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        let events = update(&mut game, &Input{ fire: true, turn: 10.0, ..Input::default() }, TICK_TIME);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], GameEvent::Fired(0));
+        assert!(matches!(events[1], GameEvent::Hit(_)));
+        assert!(game.player.fired && game.player.angle == 10.0);
+        assert_eq!(game.decals.len(), 1);
+        // The gun is only fired for one tick
+        assert!(update(&mut game, &Input::default(), TICK_TIME).is_empty());
+        assert!(!game.player.fired);
+    }
+    #[test]
+    fn test_death_and_respawn() {
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        game.player.pos_x = 400.0;
+        assert_eq!(damage::damage_player(&mut game, damage::PLAYER_HEALTH, Some((400.0, 500.0))), Some(GameEvent::Died));
+        // Dead players can't move or shoot, the death cam sinks and turns to the killer
+        let dead = Input{ movement: Movement{ forward: 1.0, ..Movement::default() }, fire: true, ..Input::default() };
+        for _ in 0..TICK_RATE / 2{
+            assert!(update(&mut game, &dead, TICK_TIME).is_empty());
+        }
+        assert_eq!((game.player.pos_x, game.player.pos_y), (400.0, 300.0));
+        assert_eq!(game.player.camera_height, DEATH_CAMERA_HEIGHT);
+        assert!((game.player.angle - 270.0).abs() < 0.001);
+        assert!(game.decals.is_empty());
+        // After RESPAWN_DELAY fire respawns at the spawn point
+        while matches!(game.player.state, PlayerState::Dead{ time, .. } if time < RESPAWN_DELAY){
+            update(&mut game, &Input::default(), TICK_TIME);
+        }
+        assert_eq!(update(&mut game, &dead, TICK_TIME), vec![GameEvent::Respawned]);
+        assert_eq!(game.player, game.spawn);
+        assert_eq!(game.player.health.health, damage::PLAYER_HEALTH);
+    }
+    #[test]
+    fn test_player_lerp() {
+        let from = Player::new(0.0, 0.0, 350.0);
+        let mut to = Player::new(10.0, 20.0, 30.0);
+        to.pitch = 10.0;
+        let middle = from.lerp(&to, 0.5);
+        assert_eq!((middle.pos_x, middle.pos_y, middle.pitch), (5.0, 10.0, 5.0));
+        // The angle goes over 0 instead of turning around
+        assert!((middle.angle - 10.0).abs() < 0.001);
+        assert!((middle.dir_x - 10_f32.to_radians().cos()).abs() < 0.001);
+        assert_eq!(from.lerp(&to, 1.0).angle, 30.0);
+    }
+    #[test]
+    fn test_camera_pitch() {
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        let level = game.camera(WINDOW_WIDTH, WINDOW_HEIGHT);
+        assert_eq!(level.horizon, (WINDOW_HEIGHT / 2) as f32);
+        // Looking up moves the horizon down by the projected height of the angle
+        game.player.look(20.0);
+        let up = game.camera(WINDOW_WIDTH, WINDOW_HEIGHT);
+        assert!((up.horizon - level.horizon - 20_f32.to_radians().tan() * up.projection).abs() < 0.01);
+        update_player(&mut game, &Movement{ pitch: -1.0, ..Movement::default() }, 0.1);
+        assert!((game.player.pitch - (20.0 - PITCH_SPEED * 0.1)).abs() < 0.001);
+        game.player.look(-180.0);
+        assert_eq!(game.player.pitch, -MAX_PITCH);
+    }
+    #[test]
+    fn test_cast_ray_levels() {
+        // The tower at column 10 has all three levels
+        let hits = cast_ray(&map::load_map(map::DEFAULT_MAP).unwrap().game_map, 600.0, 544.0, 0.0);
+        for hit in hits{
+            assert_eq!(hit.distance, 40.0);
+            assert_eq!((hit.map_x, hit.map_y), (10, 8));
+            assert_eq!((hit.pos_x, hit.pos_y), (640.0, 544.0));
+        }
+    }
+    #[test]
+    fn test_jump() {
+        // A ledge of one level next to the player, at the edge of the map
+        let mut game_map = map::GameMap::new(2, 1);
+        game_map.first_level[0][1] = 1;
+        let mut game = Game::new(Player::new(32.0, 32.0, 0.0), game_map);
+        let jump = Movement{ jump: true, ..Movement::default() };
+        update_player(&mut game, &jump, 1.0 / 60.0);
+        assert!(game.player.vel_z > 0.0 && !on_ground(&game));
+        // The peak of the jump is at JUMP_HEIGHT at every frame rate
+        let mut peak: f32 = 0.0;
+        while game.player.vel_z > 0.0{
+            update_player(&mut game, &Movement::default(), 1.0 / 60.0);
+            peak = peak.max(game.player.pos_z);
+        }
+        assert!((peak - JUMP_HEIGHT).abs() < 1.0);
+        // Walking into the ledge doesn't climb it, jumping while running gets on top of it
+        for _ in 0..60{
+            update_player(&mut game, &Movement{ forward: 1.0, ..Movement::default() }, 1.0 / 60.0);
+        }
+        assert!(game.player.pos_x <= 48.0 && game.player.pos_z == 0.0);
+        for frame in 0..60{
+            update_player(&mut game, &Movement{ forward: 1.0, jump: frame == 0, ..Movement::default() }, 1.0 / 60.0);
+        }
+        assert!(game.player.pos_x > 64.0);
+        assert_eq!(game.player.pos_z, BLOCKSIZE as f32);
+        assert!(on_ground(&game));
+        assert_eq!(game.camera(WINDOW_WIDTH, WINDOW_HEIGHT).height, BLOCKSIZE as f32 + CAMERA_HEIGHT);
+    }
+    #[test]
+    fn test_jump_into_overhang() {
+        // The second level is above the player, so the head bumps into it
+        let mut game_map = map::GameMap::new(1, 1);
+        game_map.second_level[0][0] = 1;
+        let mut game = Game::new(Player::new(32.0, 32.0, 0.0), game_map);
+        update_player(&mut game, &Movement{ jump: true, ..Movement::default() }, 1.0 / 60.0);
+        for _ in 0..60{
+            update_player(&mut game, &Movement::default(), 1.0 / 60.0);
+            assert!(game.player.pos_z + game.player.body_height() <= BLOCKSIZE as f32);
+        }
+        assert_eq!(game.player.pos_z, 0.0);
+    }
+    #[test]
+    fn test_crouch() {
+        let mut game_map = map::GameMap::new(1, 1);
+        game_map.second_level[0][0] = 1;
+        let mut game = Game::new(Player::new(32.0, 32.0, 0.0), game_map);
+        let crouching = Movement{ crouch: true, ..Movement::default() };
+        for _ in 0..60{
+            update_player(&mut game, &crouching, 1.0 / 60.0);
+        }
+        assert_eq!(game.player.camera_height, CROUCH_CAMERA_HEIGHT);
+        assert_eq!(game.camera(WINDOW_WIDTH, WINDOW_HEIGHT).height, CROUCH_CAMERA_HEIGHT);
+        // Raised into the overhang, there is no room to stand up
+        game.player.pos_z = 24.0;
+        crouch(&mut game, false, 1.0);
+        assert_eq!(game.player.camera_height, CROUCH_CAMERA_HEIGHT);
+        game.player.pos_z = 0.0;
+        crouch(&mut game, false, 1.0);
+        assert_eq!(game.player.camera_height, CAMERA_HEIGHT);
     }
 
 }
\ No newline at end of file
@@ -1,29 +1,92 @@
 extern crate sdl2;
-use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
-use std::time::Duration;
+use sdl2::event::{Event, WindowEvent};
+use std::time::Instant;
 use sdl2::render::Canvas;
 use sdl2::video::Window;
-use sdl2::image::LoadTexture;
-use sdl2::rect::Rect;
 use ya_raycaster::*;
+use ya_raycaster::framebuffer::FrameBuffer;
+use ya_raycaster::presenter::CanvasPresenter;
+use ya_raycaster::texture::TextureSet;
+use ya_raycaster::hitscan::sound_at;
+use ya_raycaster::input::{Action, Binding, MouseLook, load_bindings};
+use ya_raycaster::controller::Controllers;
+use ya_raycaster::demo::{Demo, load_demo};
+use ya_raycaster::weapon::{Arsenal, load_weapons};
+use ya_raycaster::view_model::load_sheet;
 use soloud::*;
-pub mod map;
 
-pub fn main() {
-    let mut game_instance = Game{
-        player: Player{
-            pos_x: 300.0,
-            pos_y: 300.0,
-            angle: 60.0,
-            dir_x: get_deltas(60.0).0,
-            dir_y: get_deltas(60.0).1,
-            fired: false
-        },
-        wall_rays: [[Ray::new(); RAY_COUNT], [Ray::new(); RAY_COUNT], [Ray::new(); RAY_COUNT]],
-        floor_rays: [Ray::new(); RAY_COUNT],
-        game_map: ya_raycaster::map::GAME_MAP
+const USAGE: &str = "usage: ya_raycaster [--map <path>] [--bindings <path>] [--weapons <path>] [--record <demo path>] [--play <demo path>] [--no-mouse-look] [--mouse-sensitivity <degrees per pixel>] [--invert-mouse] [--invert-mouse-y]";
+
+/// Options given on the command line
+struct Options{
+    map: String,
+    bindings: String,
+    weapons: Option<String>, // None plays with the built-in gun of Arsenal::default
+    mouse_look: MouseLook,
+    record: Option<String>, // path the inputs of the session are saved to
+    play: Option<String>, // path of a demo to replay instead of playing
+}
+
+/// Reads the command line options, the default map, bindings and weapons are used without --map, --bindings and --weapons
+fn parse_args() -> Result<Options, String>{
+    let mut args = std::env::args().skip(1);
+    let mut options = Options{
+        map: ya_raycaster::map::DEFAULT_MAP.to_string(),
+        bindings: ya_raycaster::input::DEFAULT_BINDINGS.to_string(),
+        weapons: Some(ya_raycaster::weapon::DEFAULT_WEAPONS.to_string()),
+        mouse_look: MouseLook::default(),
+        record: None,
+        play: None,
     };
+    while let Some(arg) = args.next(){
+        match arg.as_str(){
+            "--map" => options.map = args.next().ok_or("--map needs the path of a map file")?,
+            "--bindings" => options.bindings = args.next().ok_or("--bindings needs the path of a bindings file")?,
+            "--weapons" => options.weapons = Some(args.next().ok_or("--weapons needs the path of a weapons file")?),
+            "--record" => options.record = Some(args.next().ok_or("--record needs the path to save the demo to")?),
+            "--play" => options.play = Some(args.next().ok_or("--play needs the path of a demo file")?),
+            "--no-mouse-look" => options.mouse_look.enabled = false,
+            "--mouse-sensitivity" => options.mouse_look.sensitivity = args.next().and_then(|value| value.parse().ok())
+                .ok_or("--mouse-sensitivity needs the degrees to turn for a pixel of mouse motion")?,
+            "--invert-mouse" => options.mouse_look.invert = true,
+            "--invert-mouse-y" => options.mouse_look.invert_pitch = true,
+            _ => return Err(format!("Unknown argument '{}', {}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+/// Loads the sound of every weapon of the arsenal
+fn load_weapon_sounds(arsenal: &Arsenal) -> Vec<audio::Wav>{
+    arsenal.weapons.iter().map(|weapon| {
+        let mut sound = audio::Wav::default();
+        sound.load(std::path::Path::new(&weapon.sound)).expect("Couldn't load sound");
+        sound
+    }).collect()
+}
+
+pub fn main() {
+    let (options, map_file, bindings, arsenal, weapon_sheets, replay) = parse_args().and_then(|mut options| {
+        // Demos are played on the map and with the weapons they were recorded with
+        let replay = options.play.as_deref().map(load_demo).transpose()?;
+        if let Some(demo) = &replay{
+            options.map = demo.map.clone();
+            options.weapons = demo.weapons.clone();
+        }
+        let map_file = ya_raycaster::map::load_map(&options.map)?;
+        let bindings = load_bindings(&options.bindings)?;
+        let arsenal = options.weapons.as_deref().map(load_weapons).transpose()?.unwrap_or_default();
+        let weapon_sheets = arsenal.weapons.iter().map(load_sheet).collect::<Result<Vec<FrameBuffer>, String>>()?;
+        Ok((options, map_file, bindings, arsenal, weapon_sheets, replay))
+    }).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let mut game_instance = map_file.new_game();
+    game_instance.arm(arsenal.clone());
+    // Inputs are only kept when they are saved to a demo
+    let mut recording = options.record.as_ref().map(|_| Demo::new(&options.map, options.weapons.as_deref()));
+    let mut replayed_ticks = 0;
 
     // Loading sounds
     let soloud_player = Soloud::default().unwrap();
@@ -31,6 +94,7 @@ pub fn main() {
     let mut gun_hit = audio::Wav::default();
     gun_shoot.load(&std::path::Path::new("assets/sounds/gun_shoot.wav")).unwrap();
     gun_hit.load(&std::path::Path::new("assets/sounds/gun_hit.wav")).unwrap();
+    let weapon_sounds = load_weapon_sounds(&arsenal);
 
     // Video
     let sdl_context = sdl2::init().unwrap();
@@ -38,65 +102,154 @@ pub fn main() {
 
     let window: Window = video_subsystem.window("YA Raycaster", WINDOW_WIDTH, WINDOW_HEIGHT)
         .position_centered()
+        .resizable()
         .build()
         .unwrap();
     
-    let mut canvas: Canvas<Window> = window.into_canvas().target_texture().present_vsync().build().unwrap();
-    // Textures
+    let mut canvas: Canvas<Window> = window.into_canvas().present_vsync().build().unwrap();
+    // Everything is drawn to the frame buffer, the canvas only shows it
     let texture_creator = canvas.texture_creator();
-    let mut bullets: Vec<Rect> = Vec::new();
-    let mut wall_textures: [sdl2::render::Texture; 4] = [
-        texture_creator.load_texture("assets/textures/block_1.png").expect("Couldn't load texture"),
-        texture_creator.load_texture("assets/textures/block_1_dark.png").expect("Couldn't load texture"),
-        texture_creator.load_texture("assets/textures/block_2.png").expect("Couldn't load texture"),
-        texture_creator.load_texture("assets/textures/block_2_dark.png").expect("Couldn't load texture"),
-    ];
-    let mut gun_textures: [sdl2::render::Texture; 3] = [
-        texture_creator.load_texture("assets/textures/gun_normal.png").expect("Couldn't load texture"),
-        texture_creator.load_texture("assets/textures/gun_fired.png").expect("Couldn't load texture"),
-        texture_creator.load_texture("assets/textures/bullet.png").expect("Couldn't load texture"),
-    ];
-    let mut floor_textures: [sdl2::render::Texture; 1] = [
-        texture_creator.load_texture("assets/textures/block_3.png").expect("Couldn't load texture"),
-    ];
+    let mut presenter = CanvasPresenter::new(&texture_creator, WINDOW_WIDTH, WINDOW_HEIGHT);
+    let mut frame_buffer = FrameBuffer::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+    // Textures
+    let textures = TextureSet::load(&map_file.palette).expect("Couldn't load texture");
     let mut event_pump = sdl_context.event_pump().unwrap();
+    let mut controllers = Controllers::new(sdl_context.game_controller().unwrap());
+    // The mouse is grabbed while playing and released on pause
+    let mouse = sdl_context.mouse();
+    let mut paused = false;
+    mouse.set_relative_mouse_mode(options.mouse_look.enabled);
+    // The game is updated in ticks of TICK_TIME, frames are drawn between the last two ticks
+    let mut render_state = RenderState::new(&game_instance);
+    let mut previous = Snapshot::of(&game_instance);
+    let mut timestep = Timestep::default();
+    let mut input = Input::default();
+    let mut last_frame = Instant::now();
     'running: loop {
-        game_instance.player.fired = false;
         for event in event_pump.poll_iter() {
-            match event {
+            // Presses of keys and mouse buttons are handled by their actions
+            let pressed = match event {
                 Event::Quit { .. } => {
                     break 'running;
                 }
-                Event::MouseButtonDown { .. } => {
-                    game_instance.player.fired  = true;
-                    soloud_player.play(&gun_shoot);
+                Event::Window { win_event: WindowEvent::FocusLost, .. } => {
+                    paused = true;
+                    mouse.set_relative_mouse_mode(false);
+                    None
+                },
+                // Clicking into the paused window continues the game
+                Event::MouseButtonDown { .. } if paused => {
+                    paused = false;
+                    mouse.set_relative_mouse_mode(options.mouse_look.enabled);
+                    None
                 },
-                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
-                    break 'running
+                Event::MouseMotion { xrel, yrel, .. } if !paused => {
+                    input.turn += options.mouse_look.turn(xrel);
+                    input.look += options.mouse_look.pitch(yrel);
+                    None
+                },
+                Event::MouseButtonDown { mouse_btn, .. } => Some(Binding::Mouse(mouse_btn)),
+                Event::MouseWheel { y, .. } if y > 0 => Some(Binding::WheelUp),
+                Event::MouseWheel { y, .. } if y < 0 => Some(Binding::WheelDown),
+                Event::KeyDown { scancode: Some(scancode), repeat: false, .. } => Some(Binding::Key(scancode)),
+                Event::ControllerButtonDown { button, .. } => Some(Binding::Controller(button)),
+                Event::ControllerAxisMotion { which, axis, value, .. } => controllers.trigger_motion(which, axis, value),
+                // Controllers can be plugged in while playing, pulling one out pauses like losing focus
+                Event::ControllerDeviceAdded { which, .. } => {
+                    if let Err(err) = controllers.add(which) {
+                        eprintln!("{}", err);
+                    }
+                    None
+                },
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.remove(which);
+                    paused = true;
+                    mouse.set_relative_mouse_mode(false);
+                    None
+                },
+                // The picture is always rendered at the window's resolution
+                Event::Window { win_event: WindowEvent::SizeChanged(width, height), .. } => {
+                    presenter = CanvasPresenter::new(&texture_creator, width as u32, height as u32);
+                    frame_buffer = FrameBuffer::new(width as u32, height as u32);
+                    None
                 },
 
-                _ => {}
+                _ => None
+            };
+            for action in pressed.map(|binding| bindings.actions(binding)).unwrap_or_default(){
+                match action {
+                    Action::Quit => break 'running,
+                    Action::Pause => {
+                        paused = !paused;
+                        mouse.set_relative_mouse_mode(options.mouse_look.enabled && !paused);
+                    },
+                    Action::Fire if !paused => input.fire = true,
+                    Action::Reload if !paused => input.reload = true,
+                    Action::NextWeapon if !paused => input.cycle += 1,
+                    Action::PreviousWeapon if !paused => input.cycle -= 1,
+                    Action::Weapon(idx) if !paused => input.select = Some(*idx),
+                    // Held actions are read every tick by held_actions
+                    _ => {}
+                }
             }
         }
-        // Resets screen to black, if not hall of mirrors effect will be displayed
-        canvas.set_draw_color(BLACK);
-        canvas.clear();
         // ** //
-        move_player(&event_pump, &mut game_instance);
-        get_rays(&mut game_instance, &mut canvas);
-        draw_rays(&mut canvas, game_instance, &mut wall_textures, &mut floor_textures);
-        canvas.set_scale(1.0, 1.0).expect("Couldn't scale the canvas");
-        draw_2d_world(&mut canvas, game_instance, &mut gun_textures);
-        if game_instance.player.fired { bullets = fire(game_instance);}
-        if !bullets.is_empty(){
-            let bullet = Rect::new(0, 0, 64, 64); // src
-            let position = bullets.pop().unwrap(); // dst
-            canvas.copy(&gun_textures[2], bullet, position).expect("Couldn't draw the bullet");
-
+        let now = Instant::now();
+        let ticks = if paused {0} else {timestep.advance(now.duration_since(last_frame).as_secs_f32())};
+        last_frame = now;
+        for _ in 0..ticks{
+            let held = held_actions(&event_pump, &bindings, &controllers);
+            input.movement = Movement::from_actions(&held).combine(controllers.movement());
+            // Holding fire keeps shooting at the fire rate of the weapon
+            input.fire |= held.contains(&Action::Fire);
+            // A replayed demo gives the input instead of the player and pauses when it ends
+            if let Some(demo) = &replay{
+                match demo.inputs.get(replayed_ticks) {
+                    Some(replayed) => input = *replayed,
+                    None => {
+                        paused = true;
+                        mouse.set_relative_mouse_mode(false);
+                        break;
+                    },
+                }
+                replayed_ticks += 1;
+            }
+            if let Some(recording) = &mut recording{
+                recording.record(&input);
+            }
+            previous = Snapshot::of(&game_instance);
+            for game_event in update(&mut game_instance, &input, TICK_TIME){
+                match game_event {
+                    GameEvent::Fired(idx) => {
+                        soloud_player.play(&weapon_sounds[idx]);
+                    },
+                    // The impact is heard from where the shot hit
+                    GameEvent::Hit(hit) => {
+                        let (volume, pan) = sound_at(&game_instance.player, hit.pos_x, hit.pos_y);
+                        soloud_player.play_ex(&gun_hit, volume, pan, false, Handle::PRIMARY);
+                    },
+                    GameEvent::EnemyFired(enemy) => {
+                        let (volume, pan) = sound_at(&game_instance.player, enemy.pos_x, enemy.pos_y);
+                        soloud_player.play_ex(&gun_shoot, volume, pan, false, Handle::PRIMARY);
+                    },
+                    GameEvent::Hurt(_) => {
+                        soloud_player.play(&gun_hit);
+                    },
+                    GameEvent::PickedUp(_) | GameEvent::Killed(_) | GameEvent::Died | GameEvent::Respawned
+                        | GameEvent::Reloading(_) | GameEvent::Switched(_) | GameEvent::OutOfAmmo => {},
+                }
+            }
+            // Mouse motion and presses are used by the first tick that sees them
+            input = Input::default();
         }
-         // Put changes to the screen
-        canvas.present();
+        render(&mut frame_buffer, &mut render_state, &game_instance, &previous, timestep.alpha(), &textures, &weapon_sheets);
+         // Put changes to the screen, the canvas waits for vsync so frames aren't drawn faster than the screen shows them
+        presenter.present(&mut canvas, &frame_buffer);
         // ** //
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    }
+    if let (Some(path), Some(recording)) = (&options.record, &recording){
+        if let Err(err) = recording.save(path){
+            eprintln!("{}", err);
+        }
     }
 }
\ No newline at end of file
@@ -1,15 +1,43 @@
-#[derive(Debug, Copy, Clone)]
+use std::fs;
+use crate::sprite::Sprite;
+use crate::entity::{Behaviour, Entity, Item, BEHAVIOUR_NAMES, ITEM_USAGE};
+use crate::damage::Hazard;
+use crate::texture::Palette;
+use crate::{Game, Player, BLOCKSIZE};
+
+/// Map loaded when the binary isn't given one with --map
+pub const DEFAULT_MAP: &str = "assets/maps/default.map";
+
+/// Layers of the map, indexed [row][column]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GameMap{
-    pub floors: [[i32; crate::MAP_LENGTH]; crate::MAP_WIDTH],
-    pub first_level: [[i32; crate::MAP_LENGTH]; crate::MAP_WIDTH],
-    pub second_level: [[i32; crate::MAP_LENGTH]; crate::MAP_WIDTH],
-    pub third_level: [[i32; crate::MAP_LENGTH]; crate::MAP_WIDTH],
+    pub width: usize, // columns of every layer
+    pub height: usize, // rows of every layer
+    pub floors: Vec<Vec<i32>>,
+    pub ceilings: Vec<Vec<i32>>, // 0 leaves the cell open to the sky
+    pub first_level: Vec<Vec<i32>>,
+    pub second_level: Vec<Vec<i32>>,
+    pub third_level: Vec<Vec<i32>>,
 }
 
 impl GameMap{
+    /// Returns a map of the given size with every cell of every layer empty
+    pub fn new(width: usize, height: usize) -> GameMap{
+        let layer = vec![vec![0; width]; height];
+        GameMap{
+            width,
+            height,
+            floors: layer.clone(),
+            ceilings: layer.clone(),
+            first_level: layer.clone(),
+            second_level: layer.clone(),
+            third_level: layer,
+        }
+    }
+
     pub fn get_level(&self, idx: i32, pos_x: f32, pos_y: f32) -> i32{
-    let idx_y = pos_x as usize / 64 as usize;
-    let idx_x = pos_y as usize / 64 as usize;
+    let idx_y = pos_x as usize / BLOCKSIZE as usize;
+    let idx_x = pos_y as usize / BLOCKSIZE as usize;
     if idx == 0{
         return self.first_level[idx_x][idx_y];
     }
@@ -23,81 +51,384 @@ impl GameMap{
         return 1;
     }
     }
+
+    /// Returns the floor tile at the given position
+    pub fn get_floor(&self, pos_x: f32, pos_y: f32) -> i32{
+        self.floors[pos_y as usize / BLOCKSIZE as usize][pos_x as usize / BLOCKSIZE as usize]
+    }
+
+    /// Returns the ceiling tile at the given position
+    pub fn get_ceiling(&self, pos_x: f32, pos_y: f32) -> i32{
+        self.ceilings[pos_y as usize / BLOCKSIZE as usize][pos_x as usize / BLOCKSIZE as usize]
+    }
+
+    /// Returns the value of the cell at given column and row of the level
+    pub fn get_cell(&self, idx: usize, map_x: usize, map_y: usize) -> i32{
+        match idx{
+            0 => self.first_level[map_y][map_x],
+            1 => self.second_level[map_y][map_x],
+            2 => self.third_level[map_y][map_x],
+            _ => 1,
+        }
+    }
+
+    /// Returns the top of the highest level at the position that is at most max_ground high(0 for the floor),
+    /// None if the position is outside of the map or a body of the given height standing there would go into a level
+    pub fn standing_height(&self, pos_x: f32, pos_y: f32, max_ground: f32, body_height: f32) -> Option<f32>{
+        self.standing_height_around(pos_x, pos_y, 0.0, max_ground, body_height)
+    }
+
+    /// Same as standing_height for a round body with the given radius, it stands on the highest ground under it
+    pub fn standing_height_around(&self, pos_x: f32, pos_y: f32, radius: f32, max_ground: f32, body_height: f32) -> Option<f32>{
+        let block = BLOCKSIZE as f32;
+        let cells = self.cells_around(pos_x, pos_y, radius)?;
+        let ground = cells.iter().flat_map(|cell| self.solid_levels(*cell)).map(|bottom| bottom + block).filter(|top| *top <= max_ground).fold(0.0, f32::max);
+        // The body has to fit on every cell under it
+        if !self.fits_cells(&cells, ground, body_height) {return None;}
+        Some(ground)
+    }
+
+    /// Returns true if a round body with its feet at pos_z doesn't go into any level or out of the map
+    pub fn fits_around(&self, pos_x: f32, pos_y: f32, radius: f32, pos_z: f32, body_height: f32) -> bool{
+        self.cells_around(pos_x, pos_y, radius).is_some_and(|cells| self.fits_cells(&cells, pos_z, body_height))
+    }
+
+    /// Returns the cells a circle is on, None if it is out of the map
+    fn cells_around(&self, pos_x: f32, pos_y: f32, radius: f32) -> Option<Vec<(usize, usize)>>{
+        let block = BLOCKSIZE as f32;
+        let center = ((pos_x / block).floor() as i32, (pos_y / block).floor() as i32);
+        let mut cells: Vec<(usize, usize)> = Vec::new();
+        for map_y in ((pos_y - radius) / block).floor() as i32..=((pos_y + radius) / block).floor() as i32{
+            for map_x in ((pos_x - radius) / block).floor() as i32..=((pos_x + radius) / block).floor() as i32{
+                // Cells the circle only touches at the edge don't count, so it can stand flush against a wall
+                let closest_x = pos_x.clamp(map_x as f32 * block, (map_x + 1) as f32 * block);
+                let closest_y = pos_y.clamp(map_y as f32 * block, (map_y + 1) as f32 * block);
+                let (offset_x, offset_y) = (pos_x - closest_x, pos_y - closest_y);
+                if (map_x, map_y) != center && offset_x * offset_x + offset_y * offset_y >= radius * radius {continue;}
+                if map_x < 0 || map_y < 0 || map_x as usize >= self.width || map_y as usize >= self.height {return None;}
+                cells.push((map_x as usize, map_y as usize));
+            }
+        }
+        Some(cells)
+    }
+
+    /// Returns the bottoms of the levels that aren't empty in the cell
+    fn solid_levels(&self, (map_x, map_y): (usize, usize)) -> impl Iterator<Item = f32> + '_{
+        (0..3).filter(move |idx| self.get_cell(*idx, map_x, map_y) != 0).map(|idx| idx as f32 * BLOCKSIZE as f32)
+    }
+
+    fn fits_cells(&self, cells: &[(usize, usize)], pos_z: f32, body_height: f32) -> bool{
+        !cells.iter().flat_map(|cell| self.solid_levels(*cell)).any(|bottom| bottom < pos_z + body_height && bottom + BLOCKSIZE as f32 > pos_z)
+    }
+}
+
+/// Everything a map file describes
+#[derive(Debug, Clone)]
+pub struct MapFile{
+    pub game_map: GameMap,
+    pub spawn: Player, // where the player starts
+    pub palette: Palette, // textures of the tiles and the sprites
+    pub sprites: Vec<Sprite>,
+    pub entities: Vec<Entity>,
+    pub hazards: Vec<Hazard>,
+}
+
+impl MapFile{
+    /// Returns the game at the start of the map, with the player at the spawn point
+    pub fn new_game(&self) -> Game{
+        let mut game = Game::new(self.spawn, self.game_map.clone());
+        game.sprites = self.sprites.clone();
+        game.entities = self.entities.clone();
+        game.hazards = self.hazards.clone();
+        game
+    }
+}
+
+/// Reads the map file at the given path, see assets/maps/default.map for the format
+pub fn load_map(path: &str) -> Result<MapFile, String>{
+    let source = fs::read_to_string(path).map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+    parse_map(&source).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Parses the text of a map file, errors about a single line start with its number
+pub fn parse_map(source: &str) -> Result<MapFile, String>{
+    let mut size: Option<(usize, usize)> = None;
+    let mut spawn: Option<(f32, f32, f32, usize)> = None;
+    let mut palette = Palette::default();
+    let mut sprites: Vec<(Sprite, usize)> = Vec::new();
+    let mut entities: Vec<(Entity, usize)> = Vec::new();
+    let mut hazards: Vec<(Hazard, usize)> = Vec::new();
+    let mut layers: [Option<Vec<Vec<i32>>>; 5] = Default::default(); // floor, ceiling, levels 1 to 3
+    // Index of the layer whose rows are being read
+    let mut reading: Option<usize> = None;
+    let mut last_line = 0;
+    for (line_idx, line) in source.lines().enumerate(){
+        let line_number = line_idx + 1;
+        last_line = line_number;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {continue;}
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let error = |message: String| format!("line {}: {}", line_number, message);
+        if let Some(layer_idx) = reading{
+            let (width, height) = size.expect("Layers are only read after the size");
+            let rows = layers[layer_idx].get_or_insert_with(Vec::new);
+            if words.len() != width{
+                return Err(error(format!("row {} of layer {} has {} cells, expected {}", rows.len() + 1, LAYER_NAMES[layer_idx], words.len(), width)));
+            }
+            let row = words.iter().map(|word| word.parse::<i32>().ok().filter(|tile| *tile >= 0))
+                .collect::<Option<Vec<i32>>>()
+                .ok_or_else(|| error(format!("row {} of layer {} should only have tiles of 0 or more", rows.len() + 1, LAYER_NAMES[layer_idx])))?;
+            rows.push(row);
+            if rows.len() == height {reading = None;}
+            continue;
+        }
+        match words[0]{
+            "size" => {
+                let [width, height] = parse_numbers::<usize, 2>(&words).map_err(error)?;
+                if width == 0 || height == 0 {return Err(error("size of the map can't be 0".to_string()));}
+                if size.is_some() {return Err(error("size is given more than once".to_string()));}
+                size = Some((width, height));
+            },
+            "spawn" => {
+                let [pos_x, pos_y, angle] = parse_numbers::<f32, 3>(&words).map_err(error)?;
+                spawn = Some((pos_x, pos_y, angle, line_number));
+            },
+            "texture" => match (words.get(1).copied(), words.get(2..).unwrap_or_default()){
+                (Some("wall"), [light]) => palette.walls.push((light.to_string(), None)),
+                (Some("wall"), [light, dark]) => palette.walls.push((light.to_string(), Some(dark.to_string()))),
+                (Some("floor"), [path]) => palette.floors.push(path.to_string()),
+                (Some("sprite"), [path]) => palette.sprites.push(path.to_string()),
+                _ => return Err(error("expected 'texture wall <path> [dark path]', 'texture floor <path>' or 'texture sprite <path>'".to_string())),
+            },
+            "sprite" => {
+                let [pos_x, pos_y, texture] = parse_numbers::<f32, 3>(&words).map_err(error)?;
+                if texture < 0.0 || texture.fract() != 0.0 {return Err(error("sprite texture should be an index".to_string()));}
+                sprites.push((Sprite{ pos_x, pos_y, texture: texture as usize }, line_number));
+            },
+            "entity" => {
+                let usage = || error(format!("expected 'entity <{}> <x> <y> <angle> <texture>'", BEHAVIOUR_NAMES.join("|")));
+                if words.len() < 6 {return Err(usage());}
+                // Pickups end with what they give
+                let behaviour = match words[1]{
+                    "prop" if words.len() == 6 => Behaviour::Prop,
+                    "enemy" if words.len() == 6 => Behaviour::Enemy,
+                    "pickup" => Behaviour::Pickup(Item::parse(&words[6..])
+                        .ok_or_else(|| error(format!("expected 'entity pickup <x> <y> <angle> <texture> {}'", ITEM_USAGE)))?),
+                    _ => return Err(usage()),
+                };
+                let [pos_x, pos_y, angle, texture] = parse_numbers::<f32, 4>(&words[1..6]).map_err(|_| usage())?;
+                if texture < 0.0 || texture.fract() != 0.0 {return Err(error("entity texture should be an index".to_string()));}
+                entities.push((Entity::new(behaviour, pos_x, pos_y, angle.rem_euclid(360.0), texture as usize), line_number));
+            },
+            "hazard" => {
+                let usage = || error("expected 'hazard <column> <row> <damage>'".to_string());
+                if words.len() != 4 {return Err(usage());}
+                let [map_x, map_y] = parse_numbers::<usize, 2>(&words[..3]).map_err(|_| usage())?;
+                let damage = words[3].parse::<i32>().map_err(|_| usage())?;
+                if damage <= 0 {return Err(error("hazard damage should be more than 0".to_string()));}
+                hazards.push((Hazard{ map_x, map_y, damage }, line_number));
+            },
+            "layer" => {
+                if size.is_none() {return Err(error("size should be given before the layers".to_string()));}
+                let layer_idx = words.get(1).and_then(|name| LAYER_NAMES.iter().position(|layer| layer == name)).filter(|_| words.len() == 2)
+                    .ok_or_else(|| error(format!("expected 'layer <{}>'", LAYER_NAMES.join("|"))))?;
+                if layers[layer_idx].is_some() {return Err(error(format!("layer {} is given more than once", LAYER_NAMES[layer_idx])));}
+                reading = Some(layer_idx);
+            },
+            other => return Err(error(format!("unknown keyword '{}'", other))),
+        }
+    }
+    if let Some(layer_idx) = reading{
+        return Err(format!("line {}: layer {} ended before all of its rows", last_line, LAYER_NAMES[layer_idx]));
+    }
+
+    let (width, height) = size.ok_or("size of the map is missing")?;
+    if layers[2].is_none() {return Err("layer 1 is missing".to_string());}
+    if palette.walls.is_empty() {return Err("the map needs at least one wall texture".to_string());}
+    let mut game_map = GameMap::new(width, height);
+    let [floors, ceilings, first_level, second_level, third_level] = layers;
+    for (layer, rows) in [&mut game_map.floors, &mut game_map.ceilings, &mut game_map.first_level, &mut game_map.second_level, &mut game_map.third_level]
+        .into_iter().zip([floors, ceilings, first_level, second_level, third_level]){
+        if let Some(rows) = rows {*layer = rows;}
+    }
+    // Every tile needs a texture in the palette
+    for (layer_idx, layer) in [&game_map.floors, &game_map.ceilings, &game_map.first_level, &game_map.second_level, &game_map.third_level].iter().enumerate(){
+        let textures = if layer_idx < 2 {palette.floors.len()} else {palette.walls.len()};
+        for (map_y, row) in layer.iter().enumerate(){
+            if let Some(map_x) = row.iter().position(|tile| *tile as usize > textures){
+                return Err(format!("tile {} at column {}, row {} of layer {} has no texture", row[map_x], map_x + 1, map_y + 1, LAYER_NAMES[layer_idx]));
+            }
+        }
+    }
+
+    let (pos_x, pos_y, angle, spawn_line) = spawn.ok_or("spawn point of the player is missing")?;
+    let inside = |pos_x: f32, pos_y: f32| pos_x >= 0.0 && pos_y >= 0.0
+        && pos_x < (width as u32 * BLOCKSIZE) as f32 && pos_y < (height as u32 * BLOCKSIZE) as f32;
+    if !inside(pos_x, pos_y) || game_map.get_level(0, pos_x, pos_y) != 0{
+        return Err(format!("line {}: spawn point should be in an empty cell of the map", spawn_line));
+    }
+    for (sprite, line_number) in &sprites{
+        if !inside(sprite.pos_x, sprite.pos_y) {return Err(format!("line {}: sprite is outside of the map", line_number));}
+        if sprite.texture >= palette.sprites.len() {return Err(format!("line {}: sprite texture {} isn't in the palette", line_number, sprite.texture));}
+    }
+    for (entity, line_number) in &entities{
+        if !inside(entity.pos_x, entity.pos_y) {return Err(format!("line {}: entity is outside of the map", line_number));}
+        if entity.texture >= palette.sprites.len() {return Err(format!("line {}: entity texture {} isn't in the palette", line_number, entity.texture));}
+    }
+    for (hazard, line_number) in &hazards{
+        if hazard.map_x >= width || hazard.map_y >= height {return Err(format!("line {}: hazard is outside of the map", line_number));}
+    }
+    let angle = angle.rem_euclid(360.0);
+    Ok(MapFile{
+        game_map,
+        spawn: Player::new(pos_x, pos_y, angle),
+        palette,
+        sprites: sprites.into_iter().map(|(sprite, _)| sprite).collect(),
+        entities: entities.into_iter().map(|(entity, _)| entity).collect(),
+        hazards: hazards.into_iter().map(|(hazard, _)| hazard).collect(),
+    })
+}
+
+/// Names of the layers in the map files
+const LAYER_NAMES: [&str; 5] = ["floor", "ceiling", "1", "2", "3"];
+
+/// Parses the words after the keyword as N numbers
+fn parse_numbers<T: std::str::FromStr, const N: usize>(words: &[&str]) -> Result<[T; N], String>{
+    let usage = || format!("'{}' expects {} numbers", words[0], N);
+    if words.len() != N + 1 {return Err(usage());}
+    let numbers = words[1..].iter().map(|word| word.parse::<T>().ok()).collect::<Option<Vec<T>>>().ok_or_else(usage)?;
+    numbers.try_into().map_err(|_| usage())
 }
 
-pub const GAME_MAP: GameMap = GameMap{
-    floors: [
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-    ],
-    first_level: [
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 1, 0, 0, 2, 2, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-    ],
-    second_level: [
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-    ],
-    third_level: [
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-    ],
-};
- 
 
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A valid 3x3 map, tests break it line by line
+    const SMALL_MAP: &str = "size 3 3
+spawn 96 96 90
+texture wall wall.png
+texture floor floor.png
+texture sprite barrel.png
+sprite 90 100 0
+layer 1
+1 1 1
+1 0 1  # the only empty cell
+1 1 1
+";
+
+    #[test]
+    fn test_parse_map() {
+        let map_file = parse_map(SMALL_MAP).unwrap();
+        assert_eq!((map_file.game_map.width, map_file.game_map.height), (3, 3));
+        assert_eq!(map_file.game_map.first_level[1], vec![1, 0, 1]);
+        // Layers that aren't given are empty
+        assert_eq!(map_file.game_map.ceilings, vec![vec![0; 3]; 3]);
+        assert_eq!((map_file.spawn.pos_x, map_file.spawn.pos_y, map_file.spawn.angle), (96.0, 96.0, 90.0));
+        assert_eq!(map_file.palette.walls, vec![("wall.png".to_string(), None)]);
+        assert_eq!(map_file.sprites.len(), 1);
+        let map_file = parse_map(&format!("{}entity enemy 100 90 -90 0\nentity pickup 96 96 0 0 health 10", SMALL_MAP)).unwrap();
+        assert_eq!(map_file.entities, vec![
+            Entity::new(Behaviour::Enemy, 100.0, 90.0, 270.0, 0),
+            Entity::new(Behaviour::Pickup(Item::Health(10)), 96.0, 96.0, 0.0, 0),
+        ]);
+        assert_eq!(map_file.new_game().entities, map_file.entities);
+        let map_file = parse_map(&format!("{}hazard 1 1 5", SMALL_MAP)).unwrap();
+        assert_eq!(map_file.new_game().hazards, vec![Hazard{ map_x: 1, map_y: 1, damage: 5 }]);
+    }
+
+    #[test]
+    fn test_default_map() {
+        let map_file = load_map(DEFAULT_MAP).unwrap();
+        assert_eq!((map_file.game_map.width, map_file.game_map.height), (16, 16));
+        assert_eq!(map_file.game_map.get_cell(2, 10, 8), 1);
+        assert_eq!(map_file.palette.walls.len(), 2);
+        assert!(map_file.entities.iter().any(|entity| entity.behaviour == Behaviour::Enemy));
+    }
+
+    #[test]
+    fn test_standing_height() {
+        // A wall, a ledge of one level and an overhang on the second level
+        let mut game_map = GameMap::new(4, 1);
+        game_map.first_level[0][0] = 1;
+        game_map.second_level[0][0] = 1;
+        game_map.first_level[0][1] = 1;
+        game_map.second_level[0][2] = 1;
+        let body = 48.0;
+        assert_eq!(game_map.standing_height(32.0, 32.0, 0.0, body), None);
+        assert_eq!(game_map.standing_height(32.0, 32.0, 500.0, body), Some(128.0));
+        assert_eq!(game_map.standing_height(96.0, 32.0, 16.0, body), None);
+        assert_eq!(game_map.standing_height(96.0, 32.0, 64.0, body), Some(64.0));
+        assert_eq!(game_map.standing_height(160.0, 32.0, 16.0, body), Some(0.0));
+        assert_eq!(game_map.standing_height(160.0, 32.0, 16.0, 80.0), None);
+        assert_eq!(game_map.standing_height(224.0, 32.0, 0.0, body), Some(0.0));
+        assert_eq!(game_map.standing_height(300.0, 32.0, 0.0, body), None);
+    }
+
+    #[test]
+    fn test_standing_height_around() {
+        // A block with an empty cell on both sides and a block diagonal to it
+        let mut game_map = GameMap::new(3, 3);
+        game_map.first_level[1][1] = 1;
+        game_map.first_level[0][2] = 1;
+        let (radius, body) = (16.0, 48.0);
+        // Flush against the side of the block, then a little into it
+        assert_eq!(game_map.standing_height_around(48.0, 96.0, radius, 0.0, body), Some(0.0));
+        assert_eq!(game_map.standing_height_around(49.0, 96.0, radius, 0.0, body), None);
+        // Near the corner of the block the circle is out of it even though its bounding box is in it
+        assert_eq!(game_map.standing_height_around(52.0, 52.0, radius, 0.0, body), Some(0.0));
+        assert_eq!(game_map.standing_height_around(56.0, 56.0, radius, 0.0, body), None);
+        // Between the two corners that touch, the gap is too narrow
+        assert_eq!(game_map.standing_height_around(128.0, 64.0, radius, 0.0, body), None);
+        // Half over the block, the body stands on it if it can reach its top
+        assert_eq!(game_map.standing_height_around(64.0, 96.0, radius, 64.0, body), Some(64.0));
+        // Jumping next to the block, the body goes into it until it is above its top
+        assert!(!game_map.fits_around(64.0, 96.0, radius, 40.0, body));
+        assert!(game_map.fits_around(64.0, 96.0, radius, 64.0, body));
+        // The edges of the map are walls
+        assert_eq!(game_map.standing_height_around(8.0, 160.0, radius, 0.0, body), None);
+        assert_eq!(game_map.standing_height_around(8.0, 160.0, 0.0, 0.0, body), Some(0.0));
+    }
+
+    #[test]
+    fn test_parse_map_errors() {
+        let broken = [
+            ("1 0 1  #", "1 0 #", "line 9: row 2 of layer 1 has 2 cells, expected 3"),
+            ("1 0 1  #", "1 x 1 #", "line 9: row 2 of layer 1 should only have tiles of 0 or more"),
+            ("spawn 96 96 90", "spawn 32 32 0", "line 2: spawn point should be in an empty cell of the map"),
+            ("spawn 96 96 90", "spawn 96 96", "line 2: 'spawn' expects 3 numbers"),
+            ("spawn 96 96 90\n", "", "spawn point of the player is missing"),
+            ("sprite 90 100 0", "sprite 90 100 1", "line 6: sprite texture 1 isn't in the palette"),
+            ("layer 1", "layer 4", "line 7: expected 'layer <floor|ceiling|1|2|3>'"),
+            ("texture wall wall.png", "wall wall.png", "line 3: unknown keyword 'wall'"),
+            ("texture wall wall.png", "texture wall", "line 3: expected 'texture wall <path> [dark path]', 'texture floor <path>' or 'texture sprite <path>'"),
+            ("1 1 1\n1 0", "1 2 1\n1 0", "tile 2 at column 2, row 1 of layer 1 has no texture"),
+            ("the only empty cell\n1 1 1\n", "the only empty cell\n", "line 9: layer 1 ended before all of its rows"),
+        ];
+        for (from, to, expected) in broken{
+            assert_eq!(parse_map(&SMALL_MAP.replacen(from, to, 1)).unwrap_err(), expected);
+        }
+        assert_eq!(load_map("missing.map").unwrap_err().split(':').next(), Some("Couldn't read missing.map"));
+        let broken_entities = [
+            ("entity monster 100 90 0 0", "line 11: expected 'entity <prop|pickup|enemy> <x> <y> <angle> <texture>'"),
+            ("entity enemy 100 90 0", "line 11: expected 'entity <prop|pickup|enemy> <x> <y> <angle> <texture>'"),
+            ("entity enemy 100 90 0 0 health 10", "line 11: expected 'entity <prop|pickup|enemy> <x> <y> <angle> <texture>'"),
+            ("entity pickup 100 90 0 0", "line 11: expected 'entity pickup <x> <y> <angle> <texture> health <amount>|armour <amount>|ammo <ammo type> <rounds>'"),
+            ("entity pickup 100 90 0 0 ammo bullets", "line 11: expected 'entity pickup <x> <y> <angle> <texture> health <amount>|armour <amount>|ammo <ammo type> <rounds>'"),
+            ("entity pickup 100 90 0 0 armour 0", "line 11: expected 'entity pickup <x> <y> <angle> <texture> health <amount>|armour <amount>|ammo <ammo type> <rounds>'"),
+            ("entity enemy 100 90 0 0.5", "line 11: entity texture should be an index"),
+            ("entity enemy 100 900 0 0", "line 11: entity is outside of the map"),
+            ("entity enemy 100 90 0 1", "line 11: entity texture 1 isn't in the palette"),
+            ("hazard 1 1", "line 11: expected 'hazard <column> <row> <damage>'"),
+            ("hazard 1 1 0", "line 11: hazard damage should be more than 0"),
+            ("hazard 1 1 -5", "line 11: hazard damage should be more than 0"),
+            ("hazard 1 1 4294967295", "line 11: expected 'hazard <column> <row> <damage>'"),
+            ("hazard 3 1 5", "line 11: hazard is outside of the map"),
+        ];
+        for (line, expected) in broken_entities{
+            assert_eq!(parse_map(&format!("{}{}", SMALL_MAP, line)).unwrap_err(), expected);
+        }
+    }
+}
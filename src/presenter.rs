@@ -0,0 +1,24 @@
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::{Canvas, Texture, TextureCreator};
+use sdl2::video::{Window, WindowContext};
+use crate::framebuffer::FrameBuffer;
+
+/// Puts a FrameBuffer on the SDL2 window, the rendering itself never touches the canvas
+pub struct CanvasPresenter<'a>{
+    texture: Texture<'a>,
+}
+
+impl<'a> CanvasPresenter<'a>{
+    pub fn new(texture_creator: &'a TextureCreator<WindowContext>, width: u32, height: u32) -> CanvasPresenter<'a>{
+        let texture = texture_creator.create_texture_streaming(PixelFormatEnum::RGBA32, width, height)
+            .expect("Couldn't create the frame texture");
+        CanvasPresenter{ texture }
+    }
+
+    /// Uploads the buffer to the frame texture and shows it on the canvas
+    pub fn present(&mut self, canvas: &mut Canvas<Window>, buffer: &FrameBuffer){
+        self.texture.update(None, &buffer.pixels, (buffer.width * 4) as usize).expect("Couldn't update the frame texture");
+        canvas.copy(&self.texture, None, None).expect("Couldn't draw the frame");
+        canvas.present();
+    }
+}
@@ -0,0 +1,187 @@
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Rect, Point};
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::surface::Surface;
+use sdl2::video::Window;
+use crate::framebuffer::FrameBuffer;
+
+/// Drawing backend of the raycaster, draw_rays and draw_2d_world only talk to this
+pub trait RenderTarget{
+    /// Width and height in pixels
+    fn size(&self) -> (u32, u32);
+    /// Draws a vertical line of pixels, pixels[0] goes to (x, y)
+    fn draw_column(&mut self, x: i32, y: i32, pixels: &[Color]);
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+    /// Draws src part of the image to dst, scaled and blended with alpha
+    fn blit(&mut self, image: &FrameBuffer, src: Rect, dst: Rect);
+}
+
+impl RenderTarget for FrameBuffer{
+    fn size(&self) -> (u32, u32){
+        (self.width, self.height)
+    }
+
+    fn draw_column(&mut self, x: i32, y: i32, pixels: &[Color]){
+        for (idx, color) in pixels.iter().enumerate(){
+            self.set_pixel(x, y + idx as i32, *color);
+        }
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color){
+        FrameBuffer::fill_rect(self, rect, color);
+    }
+
+    fn blit(&mut self, image: &FrameBuffer, src: Rect, dst: Rect){
+        FrameBuffer::blit(self, image, src, dst);
+    }
+}
+
+/// Draws straight to the window, only for debugging and one-off pictures: every point and blit is its own SDL call.
+/// The game draws to a FrameBuffer and shows it with presenter::CanvasPresenter instead
+impl RenderTarget for Canvas<Window>{
+    fn size(&self) -> (u32, u32){
+        self.output_size().expect("Couldn't get the canvas size")
+    }
+
+    fn draw_column(&mut self, x: i32, y: i32, pixels: &[Color]){
+        for (idx, color) in pixels.iter().enumerate(){
+            self.set_draw_color(*color);
+            self.draw_point(Point::new(x, y + idx as i32)).expect("Couldn't draw point");
+        }
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color){
+        self.set_draw_color(color);
+        Canvas::fill_rect(self, rect).expect("Couldn't fill the rect");
+    }
+
+    /// Copies the whole image to a new texture on every call, too slow to blit every frame
+    fn blit(&mut self, image: &FrameBuffer, src: Rect, dst: Rect){
+        let mut pixels = image.pixels.clone();
+        let surface = Surface::from_data(&mut pixels, image.width, image.height, image.width * 4, PixelFormatEnum::RGBA32)
+            .expect("Couldn't create the surface");
+        let texture_creator = self.texture_creator();
+        let mut texture = texture_creator.create_texture_from_surface(&surface).expect("Couldn't create the texture");
+        texture.set_blend_mode(BlendMode::Blend);
+        self.copy(&texture, src, dst).expect("Couldn't draw the image");
+    }
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    /// Doesn't draw anything, only records what it was asked to draw
+    #[derive(Default)]
+    struct MockTarget{
+        columns: Vec<(i32, i32, usize)>,
+        rects: Vec<(Rect, Color)>,
+        blits: Vec<Rect>,
+        size: Option<(u32, u32)>, // None is the size of the window
+    }
+
+    impl RenderTarget for MockTarget{
+        fn size(&self) -> (u32, u32){
+            self.size.unwrap_or((WINDOW_WIDTH, WINDOW_HEIGHT))
+        }
+        fn draw_column(&mut self, x: i32, y: i32, pixels: &[Color]){
+            self.columns.push((x, y, pixels.len()));
+        }
+        fn fill_rect(&mut self, rect: Rect, color: Color){
+            self.rects.push((rect, color));
+        }
+        fn blit(&mut self, _image: &FrameBuffer, _src: Rect, dst: Rect){
+            self.blits.push(dst);
+        }
+    }
+
+    fn test_game() -> Game{
+        Game::new(
+            Player::new(300.0, 300.0, 60.0),
+            map::load_map(map::DEFAULT_MAP).unwrap().game_map
+        )
+    }
+
+    /// Draws the rays of test_game and returns the recorded columns
+    fn draw_columns(column_width: u32) -> Vec<(i32, i32, usize)>{
+        let mut game = test_game();
+        game.column_width = column_width;
+        let mut target = MockTarget::default();
+        let wall_texture = texture::WallTexture{ light: FrameBuffer::new(1, 1), dark: FrameBuffer::new(1, 1) };
+        let textures = texture::TextureSet{ walls: vec![wall_texture], floors: Vec::new(), sprites: Vec::new() };
+        get_rays(&mut game, WINDOW_WIDTH);
+        draw_rays(&mut target, &game, &textures, &mut Vec::new());
+        assert!(target.rects.is_empty() && target.blits.is_empty());
+        target.columns
+    }
+
+    #[test]
+    fn test_draw_rays_columns() {
+        let columns = draw_columns(1);
+        // The player is inside the walls so every ray of the first level hits something
+        assert!(columns.len() >= WINDOW_WIDTH as usize);
+        for x in 0..WINDOW_WIDTH as i32{
+            assert!(columns.iter().any(|column| column.0 == x));
+        }
+        for (x, y, height) in columns{
+            assert!(x >= 0 && x < WINDOW_WIDTH as i32);
+            assert!(y >= 0 && y + height as i32 <= WINDOW_HEIGHT as i32);
+        }
+    }
+
+    #[test]
+    fn test_draw_rays_column_width() {
+        // A ray for every 8 pixels is drawn as 8 identical columns
+        let columns = draw_columns(8);
+        assert_eq!(columns.len() % 8, 0);
+        for group in columns.chunks(8){
+            assert_eq!(group[0].0 % 8, 0);
+            for (idx, column) in group.iter().enumerate(){
+                assert_eq!(*column, (group[0].0 + idx as i32, group[0].1, group[0].2));
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_2d_world_calls() {
+        let game = test_game();
+        let mut target = MockTarget::default();
+        let gun_sheets = vec![FrameBuffer::new(256, 184)];
+        draw_2d_world(&mut target, &game, &gun_sheets);
+        let blocks = game.game_map.first_level.iter().flatten().filter(|value| **value != 0).count();
+        assert_eq!(target.rects.iter().filter(|(_, color)| *color == WHITE).count(), blocks);
+        // The player on the minimap and the health bar
+        assert_eq!(target.rects.iter().filter(|(_, color)| *color == RED).count(), 2);
+        assert!(target.rects.contains(&(Rect::new(8, WINDOW_HEIGHT as i32 - 16, damage::PLAYER_HEALTH as u32, 8), RED)));
+        assert_eq!(target.blits, vec![Rect::new((WINDOW_WIDTH / 2) as i32 - 64, WINDOW_HEIGHT as i32 - 184, 128, 184)]);
+        assert!(target.columns.is_empty());
+        // Dead players don't hold the gun
+        let mut game = game;
+        game.player.state = PlayerState::Dead{ time: 0.0, killer: None };
+        let mut target = MockTarget::default();
+        draw_2d_world(&mut target, &game, &gun_sheets);
+        assert!(target.blits.is_empty());
+    }
+
+    #[test]
+    fn test_render_small_window() {
+        // The minimap doesn't fit next to the picture, it is left out instead of wrapping around
+        let game = test_game();
+        let mut target = MockTarget{ size: Some((100, 100)), ..MockTarget::default() };
+        let wall_texture = texture::WallTexture{ light: FrameBuffer::new(1, 1), dark: FrameBuffer::new(1, 1) };
+        let textures = texture::TextureSet{ walls: vec![wall_texture], floors: Vec::new(), sprites: Vec::new() };
+        render(&mut target, &mut RenderState::new(&game), &game, &Snapshot::of(&game), 1.0, &textures, &[FrameBuffer::new(256, 184)]);
+        assert!(!target.rects.iter().any(|(_, color)| *color == WHITE));
+        assert!(target.columns.iter().all(|(x, y, height)| *x >= 0 && *x < 100 && *y >= 0 && *y + *height as i32 <= 100));
+        // A map wider than the window has no minimap at any size
+        let mut wide = test_game();
+        wide.game_map = map::GameMap::new(200, 4);
+        let mut target = MockTarget{ size: Some((100, 100)), ..MockTarget::default() };
+        draw_2d_world(&mut target, &wide, &[]);
+        // Only the health bar is red, the player isn't drawn to a minimap
+        assert_eq!(target.rects.iter().filter(|(_, color)| *color == RED).count(), 1);
+    }
+}
@@ -0,0 +1,134 @@
+use sdl2::pixels::Color;
+use crate::render::RenderTarget;
+use crate::texture::TextureSet;
+use crate::{Game, BLOCKSIZE, get_deltas};
+
+/// Pixels with this color are transparent, like the pixels with 0 alpha
+pub const COLOR_KEY: Color = Color::RGB(255, 0, 255);
+
+/// Object standing on the floor, always drawn facing the player
+#[derive(Debug, Copy, Clone)]
+pub struct Sprite{
+    pub pos_x: f32,
+    pub pos_y: f32,
+    pub texture: usize, // index of the texture in TextureSet::sprites
+}
+
+/// Position and size of a sprite on the screen
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Projection{
+    pub distance: f32, // perpendicular distance to the screen, same as the walls
+    pub center_x: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+/// Projects a point on the floor to the screen, None if it is behind the player
+pub fn project(game: &Game, screen_width: u32, screen_height: u32, pos_x: f32, pos_y: f32) -> Option<Projection>{
+    let camera = game.camera(screen_width, screen_height);
+    let (dir_x, dir_y) = get_deltas(game.player.angle);
+    let (offset_x, offset_y) = (pos_x - game.player.pos_x, pos_y - game.player.pos_y);
+    let distance = offset_x * dir_x + offset_y * dir_y;
+    if distance <= 1.0 {return None;}
+    // Screen x grows to the right of the player, which is -90 degrees of the looking direction
+    let right = offset_x * -dir_y + offset_y * dir_x;
+    Some(Projection{
+        distance,
+        center_x: screen_width as f32 / 2.0 + right * camera.projection / distance,
+        top: camera.horizon + (camera.height - BLOCKSIZE as f32) * camera.projection / distance,
+        bottom: camera.horizon + camera.height * camera.projection / distance,
+    })
+}
+
+/// Draws the sprites from the farthest to the closest, pixels behind the walls of the depth buffer(filled by draw_rays) are skipped
+pub fn draw_sprites<T: RenderTarget>(target: &mut T, game: &Game, sprites: &[Sprite], textures: &TextureSet, depth_buffer: &[f32]){
+    let (width, height) = target.size();
+    let mut projected: Vec<(Projection, &Sprite)> = sprites.iter()
+        .filter_map(|sprite| project(game, width, height, sprite.pos_x, sprite.pos_y).map(|projection| (projection, sprite)))
+        .collect();
+    projected.sort_by(|a, b| b.0.distance.total_cmp(&a.0.distance));
+    for (projection, sprite) in projected{
+        let Some(texture) = textures.sprites.get(sprite.texture) else {continue};
+        let sprite_height = projection.bottom - projection.top;
+        let sprite_width = sprite_height * texture.width as f32 / texture.height as f32;
+        let left = projection.center_x - sprite_width / 2.0;
+        let first_x = left.max(0.0) as u32;
+        let last_x = ((left + sprite_width).max(0.0) as u32).min(width);
+        let first_y = projection.top.max(0.0) as u32;
+        let last_y = (projection.bottom.max(0.0) as u32).min(height);
+        for x in first_x..last_x{
+            let texture_x = (((x as f32 + 0.5 - left) / sprite_width * texture.width as f32) as u32).min(texture.width - 1);
+            // Visible pixels are drawn in runs, transparent and hidden pixels split them
+            let mut run: Vec<Color> = Vec::new();
+            let mut run_start = first_y;
+            for y in first_y..last_y{
+                let texture_y = (((y as f32 + 0.5 - projection.top) / sprite_height * texture.height as f32) as u32).min(texture.height - 1);
+                let color = texture.get_pixel(texture_x, texture_y);
+                let hidden = depth_buffer[(y * width + x) as usize] <= projection.distance;
+                if hidden || color.a == 0 || Color::RGB(color.r, color.g, color.b) == COLOR_KEY{
+                    if !run.is_empty() {target.draw_column(x as i32, run_start as i32, &run);}
+                    run.clear();
+                    run_start = y + 1;
+                    continue;
+                }
+                run.push(color);
+            }
+            if !run.is_empty() {target.draw_column(x as i32, run_start as i32, &run);}
+        }
+    }
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn test_game(angle: f32) -> Game{
+        Game::new(
+            Player::new(300.0, 300.0, angle),
+            map::load_map(map::DEFAULT_MAP).unwrap().game_map
+        )
+    }
+
+    #[test]
+    fn test_project() {
+        // Looking to the right(+X), a sprite 128 units ahead is centered and one below is on the right
+        let game = test_game(0.0);
+        let ahead = project(&game, WINDOW_WIDTH, WINDOW_HEIGHT, 428.0, 300.0).unwrap();
+        assert_eq!(ahead.distance, 128.0);
+        assert!((ahead.center_x - WINDOW_WIDTH as f32 / 2.0).abs() < 0.01);
+        assert!((ahead.bottom - ahead.top - BLOCKSIZE as f32 * game.camera(WINDOW_WIDTH, WINDOW_HEIGHT).projection / 128.0).abs() < 0.01);
+        let right = project(&game, WINDOW_WIDTH, WINDOW_HEIGHT, 428.0, 364.0).unwrap();
+        assert!(right.center_x > ahead.center_x);
+        assert!(project(&game, WINDOW_WIDTH, WINDOW_HEIGHT, 200.0, 300.0).is_none());
+    }
+
+    #[test]
+    fn test_draw_sprites_depth_and_transparency() {
+        let game = test_game(0.0);
+        let mut texture = FrameBuffer::new(2, 1);
+        texture.set_pixel(0, 0, COLOR_KEY);
+        texture.set_pixel(1, 0, GREEN);
+        let textures = texture::TextureSet{ walls: Vec::new(), floors: Vec::new(), sprites: vec![texture] };
+        let sprite = Sprite{ pos_x: 428.0, pos_y: 300.0, texture: 0 };
+        let mut frame = FrameBuffer::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+        // A wall between the player and the sprite hides the left half of the screen
+        let mut depth_buffer = vec![f32::INFINITY; (WINDOW_WIDTH * WINDOW_HEIGHT) as usize];
+        for y in 0..WINDOW_HEIGHT{
+            for x in 0..WINDOW_WIDTH / 2 + 10{
+                depth_buffer[(y * WINDOW_WIDTH + x) as usize] = 100.0;
+            }
+        }
+        draw_sprites(&mut frame, &game, &[sprite], &textures, &depth_buffer);
+        let center_y = WINDOW_HEIGHT / 2;
+        let projection = project(&game, WINDOW_WIDTH, WINDOW_HEIGHT, 428.0, 300.0).unwrap();
+        let sprite_height = (projection.bottom - projection.top) as u32;
+        // Right half of the texture is green, but the first 10 pixels are behind the wall
+        assert_eq!(frame.get_pixel(WINDOW_WIDTH / 2 + 5, center_y), Color::RGBA(0, 0, 0, 0));
+        assert_eq!(frame.get_pixel(WINDOW_WIDTH / 2 + 15, center_y), GREEN);
+        // Left half is the color key
+        assert_eq!(frame.get_pixel(WINDOW_WIDTH / 2 - sprite_height / 2, center_y), Color::RGBA(0, 0, 0, 0));
+    }
+}
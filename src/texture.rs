@@ -0,0 +1,105 @@
+use sdl2::pixels::Color;
+use crate::framebuffer::FrameBuffer;
+
+/// Used to compute the dark texture when the wall doesn't have one
+const DARK_SHADE: f32 = 0.7;
+
+#[derive(Debug, Clone)]
+pub struct WallTexture{
+    pub light: FrameBuffer, // drawn on the horizontal sides(hit_side == 0)
+    pub dark: FrameBuffer, // drawn on the vertical sides(hit_side == 1)
+}
+
+/// Paths of the textures a map uses, tile n of the map uses walls[n - 1] and floors[n - 1]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Palette{
+    pub walls: Vec<(String, Option<String>)>, // the dark texture is optional
+    pub floors: Vec<String>, // used by both floors and ceilings
+    pub sprites: Vec<String>, // Sprite::texture is an index of this
+}
+
+/// Textures of the map tiles, loaded at runtime
+#[derive(Debug, Clone)]
+pub struct TextureSet{
+    pub walls: Vec<WallTexture>,
+    pub floors: Vec<FrameBuffer>, // used by both floors and ceilings
+    pub sprites: Vec<FrameBuffer>,
+}
+
+impl TextureSet{
+    /// Loads the wall, floor and sprite textures of the palette
+    pub fn load(palette: &Palette) -> Result<TextureSet, String>{
+        let mut texture_set = TextureSet{ walls: Vec::new(), floors: Vec::new(), sprites: Vec::new() };
+        for (light_path, dark_path) in &palette.walls{
+            let light = FrameBuffer::load_png(light_path)?;
+            let dark = match dark_path{
+                Some(path) => FrameBuffer::load_png(path)?,
+                None => shade(&light, DARK_SHADE),
+            };
+            texture_set.walls.push(WallTexture{ light, dark });
+        }
+        for path in &palette.floors{
+            texture_set.floors.push(FrameBuffer::load_png(path)?);
+        }
+        for path in &palette.sprites{
+            texture_set.sprites.push(FrameBuffer::load_png(path)?);
+        }
+        Ok(texture_set)
+    }
+
+    /// Returns the texture of the wall tile, tiles without a texture use the first one
+    pub fn wall(&self, tile: i32) -> &WallTexture{
+        self.walls.get((tile - 1) as usize).unwrap_or(&self.walls[0])
+    }
+
+    /// Returns the texture of the floor or ceiling tile, None for empty(0) tiles
+    pub fn floor(&self, tile: i32) -> Option<&FrameBuffer>{
+        if tile == 0 {return None;}
+        self.floors.get((tile - 1) as usize).or(self.floors.first())
+    }
+}
+
+/// Returns a copy of the image with its colors multiplied by factor
+pub fn shade(image: &FrameBuffer, factor: f32) -> FrameBuffer{
+    let mut shaded = image.clone();
+    for y in 0..image.height{
+        for x in 0..image.width{
+            let color = image.get_pixel(x, y);
+            let dim = |channel: u8| (channel as f32 * factor) as u8;
+            shaded.set_pixel(x as i32, y as i32, Color::RGBA(dim(color.r), dim(color.g), dim(color.b), color.a));
+        }
+    }
+    shaded
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_shade() {
+        let mut image = FrameBuffer::new(2, 1);
+        image.set_pixel(0, 0, Color::RGBA(100, 200, 50, 255));
+        image.set_pixel(1, 0, Color::RGBA(10, 20, 30, 0));
+        let shaded = shade(&image, 0.5);
+        assert_eq!(shaded.get_pixel(0, 0), Color::RGBA(50, 100, 25, 255));
+        assert_eq!(shaded.get_pixel(1, 0), Color::RGBA(5, 10, 15, 0));
+    }
+    #[test]
+    fn test_wall_lookup() {
+        let texture = |width| WallTexture{ light: FrameBuffer::new(width, 1), dark: FrameBuffer::new(width, 1) };
+        let texture_set = TextureSet{ walls: vec![texture(1), texture(2)], floors: Vec::new(), sprites: Vec::new() };
+        assert_eq!(texture_set.wall(1).light.width, 1);
+        assert_eq!(texture_set.wall(2).light.width, 2);
+        // Unknown tiles fall back to the first texture
+        assert_eq!(texture_set.wall(7).light.width, 1);
+    }
+    #[test]
+    fn test_floor_lookup() {
+        let texture_set = TextureSet{ walls: Vec::new(), floors: vec![FrameBuffer::new(1, 1), FrameBuffer::new(2, 2)], sprites: Vec::new() };
+        assert!(texture_set.floor(0).is_none());
+        assert_eq!(texture_set.floor(2).unwrap().width, 2);
+        assert_eq!(texture_set.floor(5).unwrap().width, 1);
+    }
+}
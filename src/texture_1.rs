@@ -1,3072 +0,0 @@
-pub const TEXTURE_1: [i32; 32*32*3] = [85,
-80,
-77,
-48,
-44,
-41,
-38,
-34,
-32,
-36,
-32,
-29,
-36,
-34,
-33,
-51,
-49,
-47,
-55,
-52,
-50,
-54,
-50,
-47,
-51,
-48,
-46,
-50,
-44,
-41,
-49,
-44,
-41,
-50,
-45,
-41,
-52,
-44,
-38,
-52,
-43,
-38,
-52,
-46,
-41,
-53,
-46,
-42,
-49,
-42,
-38,
-42,
-37,
-34,
-39,
-34,
-31,
-36,
-32,
-29,
-69,
-63,
-60,
-85,
-75,
-68,
-82,
-67,
-55,
-83,
-67,
-54,
-81,
-72,
-66,
-85,
-74,
-65,
-83,
-78,
-75,
-93,
-89,
-86,
-94,
-89,
-85,
-85,
-80,
-76,
-82,
-76,
-73,
-84,
-79,
-76,
-125,
-119,
-115,
-117,
-112,
-108,
-44,
-40,
-38,
-46,
-42,
-40,
-101,
-98,
-96,
-129,
-124,
-122,
-122,
-118,
-115,
-116,
-112,
-108,
-110,
-105,
-102,
-87,
-80,
-76,
-83,
-77,
-72,
-85,
-78,
-73,
-87,
-78,
-70,
-78,
-68,
-61,
-84,
-76,
-69,
-88,
-80,
-74,
-82,
-67,
-54,
-75,
-67,
-61,
-44,
-37,
-33,
-68,
-64,
-61,
-118,
-109,
-103,
-109,
-103,
-98,
-103,
-97,
-92,
-101,
-93,
-87,
-103,
-94,
-88,
-113,
-98,
-86,
-117,
-108,
-103,
-127,
-118,
-111,
-122,
-113,
-106,
-113,
-104,
-98,
-112,
-105,
-99,
-112,
-106,
-102,
-106,
-99,
-94,
-117,
-111,
-106,
-58,
-54,
-51,
-63,
-60,
-57,
-111,
-107,
-104,
-119,
-113,
-109,
-116,
-111,
-108,
-105,
-100,
-95,
-95,
-88,
-84,
-79,
-74,
-70,
-81,
-73,
-68,
-79,
-70,
-64,
-78,
-69,
-62,
-75,
-66,
-58,
-75,
-66,
-59,
-88,
-78,
-71,
-83,
-74,
-68,
-116,
-109,
-104,
-64,
-59,
-55,
-82,
-77,
-74,
-116,
-110,
-105,
-97,
-91,
-88,
-93,
-87,
-83,
-92,
-84,
-78,
-93,
-83,
-75,
-99,
-87,
-78,
-96,
-87,
-80,
-105,
-97,
-90,
-109,
-92,
-79,
-107,
-100,
-95,
-103,
-97,
-91,
-105,
-97,
-91,
-107,
-101,
-96,
-112,
-106,
-101,
-60,
-56,
-54,
-73,
-69,
-66,
-114,
-110,
-108,
-100,
-94,
-90,
-100,
-93,
-89,
-103,
-98,
-94,
-85,
-80,
-76,
-80,
-74,
-69,
-78,
-70,
-64,
-76,
-68,
-61,
-74,
-65,
-58,
-71,
-61,
-54,
-73,
-63,
-56,
-73,
-64,
-57,
-74,
-65,
-58,
-113,
-107,
-102,
-65,
-59,
-55,
-84,
-79,
-76,
-117,
-112,
-108,
-106,
-99,
-94,
-104,
-97,
-91,
-104,
-98,
-94,
-99,
-91,
-85,
-99,
-91,
-86,
-90,
-83,
-78,
-95,
-88,
-83,
-104,
-93,
-85,
-106,
-94,
-85,
-105,
-99,
-94,
-107,
-100,
-95,
-113,
-107,
-103,
-115,
-107,
-102,
-58,
-53,
-49,
-75,
-71,
-69,
-109,
-104,
-100,
-93,
-85,
-79,
-87,
-79,
-72,
-96,
-91,
-87,
-88,
-83,
-79,
-84,
-78,
-74,
-81,
-74,
-69,
-76,
-69,
-63,
-78,
-70,
-64,
-65,
-57,
-51,
-77,
-71,
-65,
-75,
-68,
-63,
-80,
-72,
-65,
-91,
-83,
-77,
-65,
-60,
-55,
-79,
-74,
-71,
-120,
-116,
-112,
-106,
-101,
-96,
-100,
-94,
-89,
-97,
-91,
-85,
-97,
-91,
-87,
-101,
-96,
-91,
-96,
-90,
-86,
-101,
-95,
-91,
-103,
-98,
-94,
-102,
-94,
-88,
-103,
-97,
-92,
-102,
-96,
-91,
-117,
-110,
-105,
-116,
-108,
-103,
-53,
-48,
-44,
-61,
-56,
-53,
-97,
-91,
-87,
-82,
-74,
-68,
-78,
-70,
-64,
-81,
-74,
-68,
-78,
-70,
-63,
-77,
-69,
-64,
-78,
-70,
-63,
-76,
-68,
-62,
-78,
-71,
-67,
-82,
-76,
-71,
-76,
-69,
-63,
-82,
-74,
-69,
-88,
-80,
-74,
-96,
-88,
-81,
-62,
-56,
-52,
-87,
-84,
-81,
-117,
-113,
-110,
-103,
-97,
-93,
-101,
-96,
-91,
-103,
-96,
-91,
-94,
-86,
-81,
-104,
-98,
-93,
-110,
-104,
-100,
-102,
-94,
-89,
-108,
-102,
-96,
-106,
-101,
-96,
-106,
-100,
-96,
-106,
-99,
-94,
-104,
-96,
-91,
-106,
-99,
-94,
-54,
-49,
-45,
-63,
-59,
-55,
-80,
-72,
-67,
-75,
-67,
-60,
-76,
-68,
-62,
-81,
-73,
-68,
-82,
-75,
-69,
-71,
-63,
-56,
-71,
-64,
-58,
-79,
-72,
-67,
-80,
-73,
-67,
-90,
-82,
-76,
-86,
-78,
-72,
-84,
-76,
-70,
-86,
-79,
-72,
-93,
-83,
-76,
-66,
-60,
-55,
-83,
-79,
-76,
-117,
-111,
-108,
-113,
-108,
-105,
-98,
-92,
-87,
-96,
-87,
-79,
-102,
-95,
-90,
-108,
-103,
-99,
-106,
-100,
-95,
-105,
-99,
-95,
-106,
-99,
-94,
-108,
-102,
-98,
-102,
-97,
-93,
-104,
-98,
-93,
-96,
-89,
-83,
-95,
-88,
-82,
-53,
-48,
-44,
-62,
-56,
-51,
-74,
-66,
-61,
-76,
-68,
-61,
-76,
-68,
-63,
-86,
-79,
-74,
-80,
-72,
-67,
-78,
-70,
-64,
-84,
-75,
-68,
-91,
-83,
-77,
-90,
-83,
-77,
-90,
-83,
-78,
-88,
-81,
-75,
-80,
-73,
-67,
-86,
-77,
-71,
-95,
-87,
-81,
-57,
-51,
-47,
-84,
-79,
-77,
-113,
-108,
-104,
-110,
-104,
-100,
-103,
-95,
-89,
-101,
-92,
-85,
-102,
-94,
-89,
-103,
-97,
-92,
-99,
-93,
-89,
-100,
-95,
-92,
-99,
-93,
-89,
-105,
-99,
-95,
-90,
-84,
-78,
-88,
-80,
-74,
-90,
-83,
-78,
-93,
-87,
-82,
-53,
-45,
-39,
-55,
-47,
-42,
-74,
-66,
-60,
-74,
-65,
-58,
-78,
-71,
-66,
-83,
-76,
-71,
-79,
-73,
-68,
-85,
-78,
-72,
-87,
-79,
-73,
-95,
-87,
-81,
-92,
-82,
-74,
-102,
-96,
-92,
-93,
-86,
-80,
-91,
-83,
-77,
-81,
-72,
-65,
-87,
-79,
-72,
-59,
-53,
-48,
-80,
-75,
-72,
-111,
-106,
-102,
-114,
-109,
-104,
-106,
-98,
-92,
-101,
-93,
-87,
-97,
-91,
-87,
-99,
-93,
-88,
-100,
-93,
-89,
-101,
-95,
-91,
-107,
-101,
-97,
-99,
-92,
-87,
-93,
-86,
-81,
-89,
-82,
-78,
-90,
-82,
-77,
-90,
-82,
-77,
-54,
-47,
-42,
-57,
-49,
-43,
-75,
-63,
-54,
-75,
-61,
-50,
-89,
-78,
-70,
-89,
-81,
-74,
-95,
-87,
-82,
-94,
-88,
-83,
-103,
-97,
-93,
-102,
-95,
-90,
-102,
-97,
-93,
-101,
-96,
-91,
-96,
-89,
-83,
-84,
-75,
-68,
-85,
-76,
-69,
-89,
-82,
-76,
-59,
-54,
-49,
-77,
-71,
-68,
-105,
-99,
-95,
-100,
-94,
-90,
-106,
-100,
-95,
-106,
-100,
-95,
-97,
-91,
-87,
-103,
-97,
-93,
-96,
-90,
-86,
-97,
-91,
-86,
-97,
-91,
-87,
-99,
-92,
-87,
-92,
-85,
-80,
-88,
-82,
-77,
-93,
-87,
-82,
-98,
-92,
-86,
-54,
-47,
-41,
-67,
-59,
-53,
-87,
-77,
-70,
-85,
-71,
-59,
-91,
-80,
-72,
-91,
-79,
-70,
-105,
-94,
-84,
-103,
-92,
-83,
-102,
-96,
-91,
-112,
-105,
-100,
-116,
-111,
-108,
-104,
-96,
-91,
-96,
-90,
-85,
-93,
-83,
-75,
-88,
-81,
-76,
-91,
-83,
-76,
-62,
-55,
-51,
-73,
-68,
-63,
-104,
-99,
-95,
-103,
-96,
-90,
-107,
-102,
-97,
-98,
-92,
-87,
-100,
-94,
-89,
-95,
-89,
-85,
-97,
-90,
-84,
-95,
-88,
-83,
-89,
-84,
-80,
-92,
-85,
-80,
-91,
-84,
-80,
-95,
-87,
-82,
-93,
-87,
-82,
-109,
-102,
-98,
-56,
-50,
-46,
-67,
-60,
-55,
-92,
-83,
-77,
-87,
-79,
-72,
-91,
-83,
-78,
-94,
-84,
-76,
-106,
-98,
-91,
-105,
-94,
-85,
-109,
-101,
-94,
-116,
-108,
-102,
-123,
-117,
-112,
-107,
-102,
-99,
-96,
-91,
-87,
-93,
-84,
-77,
-94,
-88,
-83,
-91,
-84,
-78,
-61,
-53,
-47,
-71,
-66,
-62,
-99,
-92,
-86,
-92,
-84,
-79,
-95,
-88,
-83,
-97,
-90,
-85,
-102,
-95,
-90,
-97,
-90,
-84,
-93,
-85,
-80,
-87,
-80,
-74,
-86,
-79,
-73,
-96,
-88,
-82,
-91,
-84,
-78,
-90,
-84,
-79,
-101,
-94,
-89,
-106,
-99,
-94,
-55,
-49,
-44,
-67,
-59,
-54,
-92,
-84,
-79,
-93,
-87,
-82,
-96,
-90,
-85,
-98,
-89,
-82,
-103,
-96,
-90,
-112,
-105,
-100,
-120,
-115,
-111,
-127,
-122,
-118,
-113,
-107,
-102,
-110,
-101,
-94,
-101,
-95,
-91,
-94,
-87,
-81,
-97,
-92,
-88,
-98,
-91,
-86,
-59,
-53,
-49,
-64,
-58,
-54,
-88,
-80,
-74,
-95,
-84,
-75,
-89,
-80,
-74,
-91,
-82,
-76,
-100,
-92,
-86,
-86,
-78,
-71,
-87,
-78,
-71,
-85,
-78,
-72,
-89,
-81,
-75,
-91,
-84,
-78,
-93,
-86,
-80,
-92,
-85,
-79,
-100,
-91,
-84,
-109,
-100,
-94,
-47,
-42,
-38,
-62,
-55,
-50,
-99,
-92,
-86,
-95,
-88,
-83,
-94,
-89,
-85,
-99,
-90,
-84,
-106,
-96,
-88,
-108,
-101,
-96,
-108,
-102,
-97,
-111,
-105,
-99,
-117,
-110,
-104,
-102,
-90,
-82,
-95,
-86,
-78,
-97,
-90,
-85,
-92,
-86,
-81,
-95,
-89,
-84,
-53,
-48,
-43,
-72,
-62,
-54,
-92,
-83,
-76,
-90,
-80,
-73,
-83,
-75,
-68,
-89,
-81,
-76,
-90,
-82,
-76,
-91,
-83,
-76,
-83,
-75,
-69,
-89,
-80,
-73,
-92,
-83,
-77,
-93,
-86,
-81,
-90,
-80,
-73,
-93,
-84,
-78,
-89,
-80,
-72,
-82,
-72,
-64,
-34,
-29,
-25,
-52,
-47,
-43,
-92,
-85,
-80,
-96,
-89,
-85,
-99,
-93,
-89,
-104,
-97,
-92,
-117,
-106,
-98,
-119,
-112,
-107,
-114,
-108,
-103,
-111,
-105,
-100,
-117,
-111,
-107,
-108,
-95,
-84,
-98,
-82,
-69,
-90,
-76,
-65,
-86,
-78,
-72,
-77,
-69,
-63,
-45,
-39,
-34,
-44,
-37,
-32,
-75,
-66,
-60,
-83,
-74,
-68,
-88,
-80,
-74,
-94,
-86,
-80,
-85,
-77,
-71,
-86,
-78,
-72,
-81,
-73,
-66,
-76,
-66,
-59,
-81,
-73,
-66,
-82,
-73,
-65,
-87,
-75,
-66,
-89,
-80,
-74,
-55,
-47,
-41,
-40,
-33,
-28,
-40,
-36,
-33,
-36,
-31,
-29,
-63,
-57,
-53,
-74,
-68,
-63,
-79,
-72,
-67,
-81,
-74,
-69,
-79,
-71,
-64,
-90,
-84,
-80,
-79,
-71,
-66,
-78,
-72,
-67,
-67,
-60,
-54,
-77,
-65,
-56,
-76,
-66,
-58,
-81,
-64,
-49,
-64,
-55,
-48,
-50,
-42,
-36,
-39,
-33,
-29,
-41,
-36,
-32,
-43,
-38,
-33,
-55,
-50,
-46,
-50,
-45,
-40,
-52,
-46,
-42,
-52,
-45,
-41,
-48,
-42,
-37,
-46,
-39,
-33,
-50,
-42,
-35,
-49,
-41,
-36,
-57,
-49,
-43,
-62,
-51,
-44,
-59,
-51,
-44,
-89,
-79,
-72,
-91,
-81,
-73,
-90,
-85,
-82,
-82,
-76,
-72,
-87,
-80,
-76,
-86,
-80,
-76,
-88,
-81,
-76,
-90,
-82,
-76,
-78,
-68,
-60,
-41,
-31,
-23,
-33,
-25,
-20,
-34,
-30,
-27,
-66,
-61,
-57,
-81,
-74,
-70,
-82,
-73,
-66,
-77,
-69,
-63,
-73,
-65,
-59,
-72,
-64,
-59,
-74,
-67,
-62,
-77,
-70,
-66,
-81,
-75,
-71,
-78,
-70,
-64,
-77,
-70,
-65,
-78,
-72,
-68,
-77,
-71,
-66,
-55,
-48,
-43,
-38,
-33,
-30,
-48,
-42,
-38,
-69,
-63,
-60,
-74,
-67,
-61,
-81,
-72,
-66,
-83,
-75,
-70,
-105,
-94,
-85,
-101,
-94,
-88,
-104,
-97,
-91,
-102,
-96,
-91,
-100,
-93,
-88,
-111,
-104,
-100,
-111,
-105,
-101,
-116,
-108,
-101,
-116,
-103,
-93,
-74,
-65,
-57,
-38,
-34,
-30,
-64,
-56,
-50,
-113,
-103,
-95,
-108,
-98,
-90,
-90,
-80,
-72,
-98,
-90,
-85,
-89,
-83,
-78,
-96,
-89,
-83,
-100,
-94,
-90,
-103,
-96,
-91,
-105,
-98,
-92,
-103,
-91,
-81,
-100,
-93,
-87,
-100,
-93,
-87,
-103,
-93,
-86,
-94,
-86,
-80,
-49,
-44,
-40,
-76,
-70,
-65,
-92,
-85,
-80,
-83,
-76,
-71,
-91,
-83,
-76,
-110,
-100,
-92,
-91,
-82,
-75,
-90,
-82,
-75,
-88,
-80,
-74,
-89,
-80,
-72,
-95,
-85,
-76,
-95,
-88,
-82,
-104,
-95,
-88,
-115,
-107,
-102,
-112,
-97,
-84,
-96,
-83,
-72,
-37,
-31,
-27,
-72,
-67,
-63,
-96,
-88,
-82,
-94,
-86,
-79,
-92,
-84,
-78,
-88,
-79,
-72,
-89,
-82,
-77,
-85,
-78,
-72,
-90,
-84,
-79,
-92,
-85,
-79,
-89,
-80,
-72,
-90,
-83,
-78,
-85,
-74,
-66,
-95,
-82,
-73,
-101,
-85,
-73,
-104,
-92,
-84,
-70,
-64,
-59,
-90,
-84,
-79,
-87,
-81,
-76,
-76,
-69,
-64,
-79,
-70,
-64,
-90,
-82,
-75,
-82,
-74,
-68,
-90,
-79,
-71,
-91,
-72,
-55,
-93,
-84,
-76,
-97,
-89,
-82,
-103,
-96,
-90,
-99,
-91,
-85,
-107,
-100,
-94,
-121,
-109,
-99,
-93,
-83,
-75,
-34,
-30,
-27,
-71,
-66,
-63,
-84,
-74,
-66,
-92,
-82,
-73,
-89,
-82,
-75,
-82,
-73,
-67,
-95,
-86,
-80,
-83,
-77,
-72,
-87,
-83,
-79,
-84,
-78,
-74,
-80,
-72,
-66,
-78,
-68,
-60,
-83,
-71,
-62,
-91,
-73,
-60,
-87,
-75,
-66,
-109,
-94,
-83,
-71,
-64,
-60,
-85,
-78,
-74,
-84,
-77,
-71,
-78,
-71,
-66,
-73,
-66,
-61,
-76,
-69,
-65,
-79,
-71,
-64,
-88,
-69,
-54,
-92,
-77,
-65,
-100,
-90,
-82,
-98,
-87,
-78,
-103,
-96,
-91,
-107,
-101,
-96,
-108,
-101,
-96,
-122,
-114,
-109,
-81,
-74,
-68,
-36,
-31,
-27,
-68,
-62,
-57,
-88,
-77,
-69,
-86,
-74,
-64,
-79,
-70,
-63,
-81,
-72,
-66,
-84,
-76,
-70,
-84,
-76,
-70,
-85,
-80,
-75,
-82,
-76,
-71,
-79,
-71,
-65,
-76,
-69,
-64,
-70,
-60,
-53,
-76,
-64,
-54,
-83,
-71,
-61,
-96,
-80,
-67,
-69,
-60,
-54,
-87,
-79,
-72,
-88,
-80,
-75,
-85,
-78,
-73,
-76,
-69,
-63,
-78,
-71,
-65,
-84,
-73,
-65,
-86,
-67,
-52,
-89,
-75,
-63,
-101,
-87,
-76,
-102,
-91,
-81,
-105,
-97,
-91,
-108,
-102,
-97,
-111,
-106,
-102,
-116,
-110,
-105,
-89,
-81,
-75,
-38,
-33,
-28,
-82,
-70,
-63,
-83,
-74,
-67,
-83,
-74,
-66,
-79,
-70,
-62,
-77,
-67,
-59,
-70,
-58,
-50,
-82,
-70,
-61,
-73,
-65,
-59,
-81,
-72,
-65,
-78,
-71,
-65,
-77,
-69,
-64,
-77,
-70,
-64,
-87,
-74,
-63,
-98,
-90,
-84,
-108,
-96,
-86,
-75,
-64,
-56,
-102,
-96,
-91,
-89,
-83,
-78,
-88,
-81,
-76,
-78,
-70,
-64,
-81,
-73,
-66,
-99,
-88,
-79,
-92,
-75,
-61,
-95,
-82,
-71,
-105,
-89,
-75,
-108,
-92,
-79,
-112,
-101,
-93,
-109,
-103,
-98,
-120,
-115,
-112,
-131,
-126,
-122,
-96,
-87,
-81,
-40,
-32,
-27,
-67,
-59,
-52,
-91,
-83,
-78,
-81,
-73,
-66,
-83,
-73,
-65,
-82,
-72,
-65,
-75,
-63,
-54,
-78,
-66,
-57,
-84,
-69,
-58,
-81,
-71,
-63,
-83,
-75,
-69,
-80,
-71,
-64,
-83,
-77,
-72,
-82,
-72,
-64,
-103,
-90,
-79,
-109,
-98,
-89,
-72,
-63,
-57,
-105,
-98,
-93,
-98,
-90,
-83,
-87,
-81,
-76,
-89,
-81,
-75,
-91,
-84,
-79,
-95,
-78,
-64,
-95,
-85,
-76,
-98,
-90,
-84,
-105,
-97,
-90,
-116,
-105,
-96,
-116,
-105,
-95,
-113,
-108,
-103,
-128,
-120,
-114,
-131,
-125,
-121,
-94,
-87,
-81,
-36,
-30,
-26,
-83,
-78,
-74,
-95,
-87,
-82,
-87,
-79,
-73,
-86,
-79,
-74,
-92,
-86,
-81,
-74,
-66,
-60,
-80,
-70,
-62,
-76,
-68,
-61,
-78,
-66,
-58,
-80,
-71,
-65,
-86,
-77,
-70,
-83,
-77,
-73,
-86,
-74,
-66,
-91,
-83,
-76,
-101,
-93,
-86,
-73,
-63,
-55,
-104,
-92,
-82,
-109,
-98,
-89,
-95,
-89,
-83,
-90,
-79,
-69,
-91,
-79,
-69,
-92,
-77,
-64,
-95,
-86,
-79,
-97,
-89,
-82,
-99,
-89,
-81,
-110,
-100,
-91,
-107,
-102,
-98,
-105,
-99,
-96,
-121,
-116,
-111,
-133,
-126,
-120,
-98,
-92,
-87,
-38,
-29,
-23,
-82,
-74,
-68,
-90,
-82,
-75,
-86,
-78,
-72,
-87,
-77,
-70,
-88,
-81,
-76,
-83,
-68,
-58,
-82,
-71,
-63,
-85,
-76,
-69,
-80,
-70,
-63,
-80,
-73,
-69,
-82,
-74,
-67,
-84,
-78,
-73,
-92,
-84,
-77,
-102,
-93,
-87,
-103,
-93,
-85,
-78,
-62,
-50,
-102,
-88,
-78,
-102,
-88,
-78,
-96,
-89,
-83,
-92,
-84,
-77,
-95,
-81,
-70,
-92,
-77,
-66,
-89,
-70,
-55,
-97,
-80,
-67,
-95,
-81,
-69,
-92,
-82,
-75,
-97,
-90,
-86,
-108,
-102,
-98,
-118,
-111,
-105,
-123,
-114,
-107,
-91,
-84,
-79,
-41,
-31,
-24,
-70,
-63,
-57,
-99,
-91,
-84,
-92,
-84,
-79,
-95,
-88,
-83,
-91,
-85,
-80,
-89,
-82,
-76,
-86,
-77,
-71,
-85,
-76,
-69,
-80,
-71,
-64,
-80,
-73,
-68,
-74,
-67,
-62,
-82,
-76,
-72,
-95,
-87,
-81,
-102,
-94,
-87,
-114,
-98,
-86,
-80,
-68,
-58,
-97,
-85,
-75,
-107,
-95,
-85,
-91,
-83,
-77,
-92,
-79,
-68,
-93,
-82,
-73,
-84,
-70,
-59,
-92,
-70,
-52,
-94,
-77,
-64,
-87,
-78,
-71,
-91,
-85,
-80,
-103,
-94,
-87,
-99,
-91,
-86,
-106,
-99,
-93,
-121,
-113,
-107,
-97,
-89,
-82,
-41,
-32,
-25,
-77,
-72,
-69,
-105,
-98,
-94,
-95,
-89,
-84,
-90,
-84,
-79,
-85,
-78,
-72,
-92,
-85,
-80,
-83,
-76,
-71,
-81,
-73,
-67,
-88,
-79,
-71,
-85,
-75,
-68,
-84,
-76,
-70,
-96,
-81,
-70,
-106,
-94,
-85,
-107,
-96,
-87,
-109,
-92,
-79,
-80,
-62,
-47,
-108,
-97,
-87,
-102,
-83,
-68,
-85,
-74,
-65,
-79,
-70,
-64,
-80,
-70,
-62,
-78,
-70,
-63,
-85,
-73,
-64,
-81,
-71,
-64,
-91,
-82,
-75,
-91,
-84,
-78,
-94,
-87,
-82,
-100,
-91,
-84,
-107,
-98,
-91,
-122,
-115,
-110,
-92,
-85,
-80,
-35,
-30,
-26,
-79,
-73,
-70,
-110,
-105,
-101,
-98,
-92,
-87,
-89,
-82,
-77,
-86,
-80,
-75,
-89,
-82,
-77,
-89,
-80,
-74,
-90,
-82,
-76,
-88,
-78,
-70,
-87,
-76,
-67,
-82,
-69,
-59,
-89,
-71,
-57,
-88,
-75,
-65,
-97,
-79,
-65,
-105,
-92,
-83,
-73,
-63,
-55,
-94,
-84,
-77,
-94,
-85,
-78,
-86,
-77,
-71,
-77,
-66,
-59,
-76,
-67,
-60,
-85,
-71,
-60,
-85,
-76,
-70,
-82,
-73,
-66,
-91,
-83,
-77,
-95,
-88,
-83,
-97,
-90,
-85,
-104,
-97,
-91,
-103,
-94,
-87,
-111,
-105,
-100,
-91,
-85,
-80,
-32,
-29,
-26,
-81,
-76,
-73,
-113,
-108,
-104,
-95,
-89,
-85,
-84,
-77,
-71,
-89,
-82,
-76,
-91,
-83,
-78,
-93,
-86,
-80,
-86,
-80,
-74,
-88,
-77,
-69,
-86,
-79,
-74,
-85,
-76,
-70,
-84,
-73,
-65,
-94,
-79,
-66,
-101,
-86,
-75,
-97,
-89,
-83,
-65,
-56,
-50,
-95,
-87,
-81,
-92,
-82,
-74,
-84,
-72,
-62,
-77,
-66,
-58,
-82,
-64,
-52,
-83,
-75,
-68,
-89,
-75,
-65,
-91,
-80,
-71,
-97,
-89,
-81,
-103,
-96,
-91,
-106,
-100,
-96,
-108,
-101,
-95,
-108,
-102,
-98,
-110,
-103,
-98,
-77,
-72,
-67,
-32,
-28,
-26,
-75,
-70,
-67,
-94,
-87,
-82,
-99,
-93,
-88,
-86,
-79,
-73,
-88,
-80,
-74,
-96,
-87,
-80,
-99,
-92,
-87,
-98,
-92,
-88,
-98,
-89,
-82,
-91,
-83,
-78,
-89,
-78,
-69,
-93,
-80,
-69,
-92,
-84,
-78,
-97,
-90,
-84,
-94,
-84,
-76,
-65,
-58,
-52,
-94,
-85,
-78,
-106,
-97,
-90,
-89,
-79,
-72,
-78,
-70,
-63,
-87,
-74,
-64,
-88,
-80,
-73,
-86,
-79,
-73,
-83,
-75,
-69,
-88,
-79,
-72,
-90,
-83,
-77,
-104,
-96,
-91,
-98,
-90,
-83,
-103,
-95,
-90,
-90,
-83,
-77,
-50,
-45,
-41,
-33,
-28,
-25,
-46,
-41,
-37,
-79,
-72,
-67,
-85,
-78,
-73,
-75,
-66,
-60,
-73,
-65,
-59,
-84,
-75,
-69,
-93,
-86,
-81,
-87,
-79,
-73,
-91,
-83,
-77,
-92,
-83,
-76,
-82,
-73,
-66,
-82,
-70,
-60,
-87,
-78,
-71,
-89,
-77,
-67,
-67,
-59,
-52,
-34,
-29,
-26,
-90,
-81,
-74,
-98,
-86,
-76,
-85,
-76,
-69,
-83,
-75,
-69,
-79,
-71,
-64,
-48,
-41,
-36,
-47,
-42,
-37,
-44,
-38,
-33,
-44,
-38,
-33,
-45,
-39,
-34,
-47,
-42,
-38,
-45,
-39,
-34,
-45,
-39,
-33,
-44,
-37,
-32,
-39,
-36,
-34,
-47,
-43,
-41,
-38,
-34,
-31,
-38,
-32,
-28,
-46,
-39,
-34,
-45,
-37,
-31,
-42,
-34,
-28,
-43,
-36,
-30,
-46,
-40,
-35,
-50,
-42,
-35,
-47,
-40,
-34,
-49,
-41,
-35,
-48,
-41,
-35,
-49,
-39,
-31,
-48,
-39,
-32,
-53,
-43,
-35,
-39,
-33,
-29,
-33,
-30,
-27,
-37,
-32,
-28,
-53,
-46,
-41,
-58,
-53,
-50,
-50,
-44,
-39,
-47,
-41,
-37];
\ No newline at end of file
@@ -0,0 +1,283 @@
+use std::f32::consts::PI;
+use sdl2::rect::Rect;
+use crate::{Game, PLAYER_SPEED, WINDOW_HEIGHT, on_ground};
+use crate::framebuffer::FrameBuffer;
+use crate::render::RenderTarget;
+use crate::weapon::{Inventory, SWITCH_TIME, Weapon};
+
+/// Units walked in a full sway of the weapon from side to side, it dips on every step of it
+const BOB_STRIDE: f32 = 160.0;
+/// Pixels of a WINDOW_HEIGHT tall screen the weapon sways to either side at PLAYER_SPEED
+const BOB_WIDTH: f32 = 10.0;
+/// Pixels it dips on every step at PLAYER_SPEED
+const BOB_HEIGHT: f32 = 8.0;
+/// How fast the bobbing follows the speed of the player, per second
+const BOB_EASE: f32 = 8.0;
+/// Pixels the weapon kicks down when it fires
+const RECOIL_KICK: f32 = 16.0;
+/// How fast the weapon comes back up after the kick, per second
+const RECOIL_RECOVERY: f32 = 14.0;
+/// Pixels the idle frame dips halfway through reloading a weapon without reload frames
+const RELOAD_DIP: f32 = 64.0;
+
+/// Walk bobbing and recoil of the weapon the player holds, the rest of the view model follows from the inventory
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewModel{
+    pub phase: f32, // radians along the sway, goes a full turn every BOB_STRIDE walked
+    pub bob: f32, // 0 standing still to 1 walking at PLAYER_SPEED
+    pub since_shot: f32, // Inventory::since_shot of the tick, drawn between ticks like the player
+}
+
+impl Default for ViewModel{
+    /// Standing still with a weapon that hasn't fired
+    fn default() -> ViewModel{
+        ViewModel{ phase: 0.0, bob: 0.0, since_shot: f32::INFINITY }
+    }
+}
+
+/// What is drawn of the held weapon in a frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pose{
+    pub weapon: usize, // index of the drawn weapon in Arsenal::weapons, the lowered one while switching
+    pub frame: usize, // frame of its sheet
+    pub offset_x: f32, // pixels of a WINDOW_HEIGHT tall screen right of the bottom center
+    pub offset_y: f32, // pixels down from resting on the bottom of the screen
+}
+
+impl ViewModel{
+    /// Returns the view model between this and the next tick, alpha 0 is this and 1 is next
+    pub fn lerp(&self, next: &ViewModel, alpha: f32) -> ViewModel{
+        // The sway goes on over a full turn the short way around
+        let sway = (next.phase - self.phase + PI).rem_euclid(2.0 * PI) - PI;
+        ViewModel{
+            phase: (self.phase + sway * alpha).rem_euclid(2.0 * PI),
+            bob: self.bob + (next.bob - self.bob) * alpha,
+            // A shot of the next tick starts its recoil on that tick, weapons that never fired have nothing to draw between
+            since_shot: if next.since_shot < self.since_shot || self.since_shot.is_infinite() {next.since_shot}
+                else {self.since_shot + (next.since_shot - self.since_shot) * alpha},
+        }
+    }
+
+    /// Returns where the weapon is drawn and which frame: the fire frames play over the cooldown of a shot and the reload
+    /// frames over the reload, switching lowers the previous weapon out of the screen and raises the held one
+    pub fn pose(&self, weapons: &[Weapon], inventory: &Inventory) -> Pose{
+        let lowering = inventory.switching > SWITCH_TIME / 2.0;
+        let idx = if lowering {inventory.previous} else {inventory.current};
+        let weapon = &weapons[idx];
+        // 0 held up to 1 out of the screen
+        let lowered = if lowering {2.0 - inventory.switching / SWITCH_TIME * 2.0} else {inventory.switching / SWITCH_TIME * 2.0};
+        let shot = self.since_shot * weapon.fire_rate; // 1 when the next shot can be fired
+        let reloaded = 1.0 - inventory.reloading / weapon.reload_time.max(f32::MIN_POSITIVE); // 1 when reloading is done
+        let (frame, dip) = if !lowering && inventory.reloading > 0.0{
+            match in_turn(&weapon.reload_frames, reloaded){
+                Some(frame) => (frame, 0.0),
+                None => (weapon.idle_frame, (reloaded * PI).sin() * RELOAD_DIP),
+            }
+        }
+        else if !lowering && shot < 1.0{
+            (in_turn(&weapon.fire_frames, shot).unwrap_or(weapon.idle_frame), 0.0)
+        }
+        else{
+            (weapon.idle_frame, 0.0)
+        };
+        let recoil = if lowering {0.0} else {(-self.since_shot * RECOIL_RECOVERY).exp() * RECOIL_KICK};
+        Pose{
+            weapon: idx,
+            frame,
+            offset_x: self.phase.cos() * BOB_WIDTH * self.bob,
+            offset_y: self.phase.sin().abs() * BOB_HEIGHT * self.bob + recoil + dip + lowered * weapon.frame_size.1 as f32,
+        }
+    }
+}
+
+/// Returns the frame shown at progress between 0 and 1 of frames played in turn, None without frames
+fn in_turn(frames: &[usize], progress: f32) -> Option<usize>{
+    let idx = (progress.max(0.0) * frames.len() as f32) as usize;
+    frames.get(idx.min(frames.len().saturating_sub(1))).copied()
+}
+
+/// Sways the weapon with the walking of the player for a tick of delta_time seconds, it settles while standing or in the air.
+/// Runs after the weapons so the recoil starts on the tick of the shot
+pub fn update_view_model(game: &mut Game, delta_time: f32){
+    let speed = game.player.vel_x.hypot(game.player.vel_y);
+    let target = if on_ground(game) {(speed / PLAYER_SPEED).min(1.0)} else {0.0};
+    let view_model = &mut game.view_model;
+    view_model.since_shot = game.inventory.since_shot;
+    view_model.bob += (target - view_model.bob) * (1.0 - (-BOB_EASE * delta_time).exp());
+    view_model.phase = (view_model.phase + speed * delta_time / BOB_STRIDE * 2.0 * PI).rem_euclid(2.0 * PI);
+}
+
+/// Returns the part of the sheet with the frame, None if the sheet is too small for it
+pub fn frame_rect(weapon: &Weapon, sheet: &FrameBuffer, frame: usize) -> Option<Rect>{
+    let (width, height) = weapon.frame_size;
+    let columns = (sheet.width / width) as usize;
+    if columns == 0 {return None;}
+    let (x, y) = ((frame % columns) as u32 * width, (frame / columns) as u32 * height);
+    if y + height > sheet.height {return None;}
+    Some(Rect::new(x as i32, y as i32, width, height))
+}
+
+/// Loads the sprite sheet of the weapon, errors if a frame of it is outside the sheet
+pub fn load_sheet(weapon: &Weapon) -> Result<FrameBuffer, String>{
+    let sheet = FrameBuffer::load_png(&weapon.sheet)?;
+    check_frames(weapon, &sheet).map_err(|err| format!("{}: {}", weapon.sheet, err))?;
+    Ok(sheet)
+}
+
+/// Returns an error if the idle, fire or reload frames of the weapon don't fit the sheet
+fn check_frames(weapon: &Weapon, sheet: &FrameBuffer) -> Result<(), String>{
+    let frames = std::iter::once(&weapon.idle_frame).chain(&weapon.fire_frames).chain(&weapon.reload_frames);
+    match frames.copied().find(|frame| frame_rect(weapon, sheet, *frame).is_none()){
+        Some(frame) => Err(format!("frame {} of weapon '{}' is outside the {}x{} sheet", frame, weapon.name, sheet.width, sheet.height)),
+        None => Ok(()),
+    }
+}
+
+/// Draws the held weapon at the bottom center of the screen, scaled from a WINDOW_HEIGHT tall screen to the target's height.
+/// sheets has the sprite sheet of every weapon of Game::arsenal
+pub fn draw_view_model<T: RenderTarget>(target: &mut T, game: &Game, sheets: &[FrameBuffer]){
+    let (width, height) = target.size();
+    let pose = game.view_model.pose(&game.arsenal.weapons, &game.inventory);
+    let weapon = &game.arsenal.weapons[pose.weapon];
+    let Some(sheet) = sheets.get(pose.weapon) else {return;};
+    let Some(src) = frame_rect(weapon, sheet, pose.frame) else {return;};
+    let scale = height as f32 / WINDOW_HEIGHT as f32;
+    let (frame_width, frame_height) = ((weapon.frame_size.0 as f32 * scale).round(), (weapon.frame_size.1 as f32 * scale).round());
+    let x = width as f32 / 2.0 - frame_width / 2.0 + pose.offset_x * scale;
+    let y = height as f32 - frame_height + pose.offset_y * scale;
+    if y >= height as f32 {return;}
+    target.blit(sheet, src, Rect::new(x.round() as i32, y.round() as i32, frame_width as u32, frame_height as u32));
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use crate::weapon::{parse_weapons, update_weapons};
+
+    /// A pistol with fire and reload frames and a knife that dips to reload
+    const VIEW_WEAPONS: &str = "ammo_type bullets 10 10
+weapon pistol
+fire_rate 2
+damage 25
+ammo bullets
+clip 2
+reload 1
+sheet pistol.png 128 184
+idle_frame 0
+fire_frames 1 2
+reload_frames 3 4 5 6
+sound shot.wav
+weapon knife
+fire_rate 2
+damage 50
+ammo bullets
+clip 1
+reload 1
+sheet knife.png 64 64
+sound slash.wav
+";
+
+    fn test_game() -> Game{
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        game.arm(parse_weapons(VIEW_WEAPONS).unwrap());
+        game
+    }
+
+    /// Holds the input for the seconds and returns the pose after it
+    fn hold(game: &mut Game, input: &Input, seconds: f32) -> Pose{
+        for _ in 0..(seconds * TICK_RATE as f32).round() as usize{
+            update_weapons(game, input, TICK_TIME);
+            update_view_model(game, TICK_TIME);
+        }
+        game.view_model.pose(&game.arsenal.weapons, &game.inventory)
+    }
+
+    #[test]
+    fn test_fire_and_reload_frames() {
+        let mut game = test_game();
+        let idle = Input::default();
+        assert_eq!(hold(&mut game, &idle, 0.0), Pose{ weapon: 0, frame: 0, offset_x: 0.0, offset_y: 0.0 });
+        // The fire frames play over the cooldown, the weapon kicks down and comes back up
+        let fired = hold(&mut game, &Input{ fire: true, ..Input::default() }, TICK_TIME);
+        assert_eq!(fired.frame, 1);
+        assert!(fired.offset_y > RECOIL_KICK * 0.5);
+        let later = hold(&mut game, &idle, 0.3);
+        assert_eq!(later.frame, 2);
+        assert!(later.offset_y < fired.offset_y);
+        assert_eq!(hold(&mut game, &idle, 0.3).frame, 0);
+        // The second shot empties the clip, the reload frames play over the reload
+        hold(&mut game, &Input{ fire: true, ..Input::default() }, TICK_TIME);
+        assert_eq!(hold(&mut game, &idle, 0.1).frame, 3);
+        assert_eq!(hold(&mut game, &idle, 0.5).frame, 5);
+        assert_eq!(hold(&mut game, &idle, 0.5).frame, 0);
+    }
+
+    #[test]
+    fn test_reload_dip_and_switch() {
+        let mut game = test_game();
+        let idle = Input::default();
+        // Switching lowers the pistol out of the screen, then raises the knife
+        let lowering = hold(&mut game, &Input{ select: Some(1), ..Input::default() }, SWITCH_TIME * 0.4);
+        assert_eq!(lowering.weapon, 0);
+        assert!(lowering.offset_y > 92.0);
+        let raising = hold(&mut game, &idle, SWITCH_TIME * 0.3);
+        assert_eq!(raising.weapon, 1);
+        assert!(raising.offset_y > 0.0 && raising.offset_y < 64.0);
+        assert_eq!(hold(&mut game, &idle, SWITCH_TIME * 0.4).offset_y, 0.0);
+        // Without reload frames the idle frame dips and comes back up
+        hold(&mut game, &Input{ fire: true, ..Input::default() }, TICK_TIME);
+        let dipped = hold(&mut game, &idle, 0.5);
+        assert_eq!(dipped.frame, 0);
+        assert!(dipped.offset_y > RELOAD_DIP * 0.9);
+        assert!(hold(&mut game, &idle, 0.6).offset_y < 0.01);
+    }
+
+    #[test]
+    fn test_bobbing() {
+        let mut game = test_game();
+        // Walking sways the weapon, standing still settles it
+        game.player.vel_x = PLAYER_SPEED;
+        let mut sway: f32 = 0.0;
+        for _ in 0..TICK_RATE{
+            update_view_model(&mut game, TICK_TIME);
+            sway = sway.max(game.view_model.pose(&game.arsenal.weapons, &game.inventory).offset_x.abs());
+        }
+        assert!(game.view_model.bob > 0.99);
+        assert!(sway > BOB_WIDTH * 0.9);
+        game.player.vel_x = 0.0;
+        for _ in 0..TICK_RATE{
+            update_view_model(&mut game, TICK_TIME);
+        }
+        assert!(game.view_model.bob < 0.01);
+    }
+
+    #[test]
+    fn test_frame_rect() {
+        let weapon = parse_weapons(VIEW_WEAPONS).unwrap().weapons.remove(0);
+        let sheet = FrameBuffer::new(512, 368);
+        assert_eq!(frame_rect(&weapon, &sheet, 1), Some(Rect::new(128, 0, 128, 184)));
+        assert_eq!(frame_rect(&weapon, &sheet, 6), Some(Rect::new(256, 184, 128, 184)));
+        assert_eq!(frame_rect(&weapon, &sheet, 8), None);
+        // Every frame of the pistol has to fit its sheet
+        assert_eq!(check_frames(&weapon, &sheet), Ok(()));
+        assert_eq!(check_frames(&weapon, &FrameBuffer::new(512, 184)), Err("frame 4 of weapon 'pistol' is outside the 512x184 sheet".to_string()));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let from = ViewModel{ phase: 6.2, bob: 0.5, since_shot: 0.1 };
+        let to = ViewModel{ phase: 0.1, bob: 1.0, since_shot: 0.2 };
+        let middle = from.lerp(&to, 0.5);
+        // Over a full turn of the sway the short way
+        assert!(middle.phase > 6.2 || middle.phase < 0.1);
+        assert_eq!((middle.bob, middle.since_shot), (0.75, 0.15));
+        // A new shot isn't drawn before its tick is reached, weapons that never fired don't recoil
+        let fired = ViewModel{ since_shot: 0.0, ..to };
+        assert_eq!(from.lerp(&fired, 0.5).since_shot, 0.0);
+        let idle = ViewModel::default();
+        assert_eq!(idle.lerp(&idle, 0.5).since_shot, f32::INFINITY);
+    }
+}
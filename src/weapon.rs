@@ -0,0 +1,480 @@
+use std::fs;
+use std::str::FromStr;
+use crate::{Game, GameEvent, Input, TICK_RATE, entity, fire, hitscan};
+
+/// Weapons file loaded when the binary isn't given one with --weapons
+pub const DEFAULT_WEAPONS: &str = "assets/weapons.cfg";
+/// Start of the random spread of the shots, any number but 0
+const SPREAD_SEED: u32 = 0x2545_F491;
+/// Seconds of lowering the held weapon and raising the next one when switching, neither fires meanwhile
+pub const SWITCH_TIME: f32 = 0.4;
+
+/// Ammunition that is shared by the weapons using it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmmoType{
+    pub name: String,
+    pub max: u32, // most rounds the player carries, besides the loaded clips
+    pub start: u32, // rounds carried after spawning
+}
+
+/// A weapon the player can hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weapon{
+    pub name: String,
+    pub fire_rate: f32, // shots per second while fire is held
+    pub damage: i32, // health every pellet takes from what it hits
+    pub pellets: u32, // shots cast at once
+    pub spread: f32, // degrees the pellets go off to either side at most
+    pub ammo: Option<usize>, // index of the ammo type in Arsenal::ammo_types, None never runs out
+    pub clip: u32, // rounds fired before reloading, 0 fires straight from the carried ammo
+    pub reload_time: f32, // seconds
+    pub sheet: String, // path of the sprite sheet, its frames are numbered from 0 left to right, then top to bottom
+    pub frame_size: (u32, u32), // width and height of a frame of the sheet, in pixels of a WINDOW_HEIGHT tall screen
+    pub idle_frame: usize, // frame shown while the weapon is ready
+    pub fire_frames: Vec<usize>, // frames shown in turn after a shot, until the weapon can fire again
+    pub reload_frames: Vec<usize>, // frames shown in turn while reloading, the idle frame dips without them
+    pub sound: String, // path of the sound played on every shot
+}
+
+/// Every weapon and ammo type, in the order of the weapons file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arsenal{
+    pub weapons: Vec<Weapon>,
+    pub ammo_types: Vec<AmmoType>,
+}
+
+impl Default for Arsenal{
+    /// A gun that fires on every tick fire is pressed and never runs out
+    fn default() -> Arsenal{
+        Arsenal{
+            weapons: vec![Weapon{
+                name: "gun".to_string(),
+                fire_rate: TICK_RATE as f32,
+                damage: hitscan::SHOT_DAMAGE,
+                pellets: 1,
+                spread: 0.0,
+                ammo: None,
+                clip: 0,
+                reload_time: 0.0,
+                sheet: "assets/textures/gun_sheet.png".to_string(),
+                frame_size: (128, 184),
+                idle_frame: 0,
+                fire_frames: vec![1],
+                reload_frames: Vec::new(),
+                sound: "assets/sounds/gun_shoot.wav".to_string(),
+            }],
+            ammo_types: Vec::new(),
+        }
+    }
+}
+
+/// Weapons and ammo the player carries
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory{
+    pub current: usize, // index of the held weapon in Arsenal::weapons
+    pub clips: Vec<u32>, // rounds loaded in every weapon
+    pub ammo: Vec<u32>, // carried rounds of every ammo type, outside of the clips
+    pub cooldown: f32, // seconds until the held weapon can fire again
+    pub reloading: f32, // seconds left of reloading the held weapon, 0 if it isn't reloading
+    pub since_shot: f32, // seconds since the held weapon fired
+    pub switching: f32, // seconds left of switching to the held weapon, 0 if it is raised
+    pub previous: usize, // index of the weapon lowered at the start of switching
+    seed: u32, // state of the random spread, it is part of the game so demos replay the same shots
+}
+
+impl Inventory{
+    /// Returns the inventory after spawning: the first weapon is held, every clip is full and the ammo types have their start
+    pub fn new(arsenal: &Arsenal) -> Inventory{
+        Inventory{
+            current: 0,
+            clips: arsenal.weapons.iter().map(|weapon| weapon.clip).collect(),
+            ammo: arsenal.ammo_types.iter().map(|ammo_type| ammo_type.start).collect(),
+            cooldown: 0.0,
+            reloading: 0.0,
+            since_shot: f32::INFINITY,
+            switching: 0.0,
+            previous: 0,
+            seed: SPREAD_SEED,
+        }
+    }
+
+    /// Returns true if the held weapon has a round to fire without reloading
+    fn loaded(&self, weapon: &Weapon) -> bool{
+        match weapon.ammo{
+            None => true,
+            Some(_) if weapon.clip > 0 => self.clips[self.current] > 0,
+            Some(ammo_type) => self.ammo[ammo_type] > 0,
+        }
+    }
+
+    /// Returns a number between -1 and 1, the same ones in the same order every game
+    fn random(&mut self) -> f32{
+        // Xorshift
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed >> 8) as f32 / (1 << 23) as f32 - 1.0
+    }
+}
+
+/// Lowers the held weapon and raises the one at the index over SWITCH_TIME, reloading is cancelled.
+/// Returns Switched if it is another weapon of the arsenal
+pub fn switch_weapon(game: &mut Game, idx: usize) -> Option<GameEvent>{
+    if idx >= game.arsenal.weapons.len() || idx == game.inventory.current {return None;}
+    game.inventory.previous = game.inventory.current;
+    game.inventory.current = idx;
+    game.inventory.switching = SWITCH_TIME;
+    game.inventory.reloading = 0.0;
+    game.inventory.since_shot = f32::INFINITY;
+    Some(GameEvent::Switched(idx))
+}
+
+/// Starts reloading the held weapon if its clip isn't full and there is ammo for it, returns Reloading if it started
+pub fn reload(game: &mut Game) -> Option<GameEvent>{
+    let inventory = &game.inventory;
+    let weapon = &game.arsenal.weapons[inventory.current];
+    let ammo_type = weapon.ammo?;
+    if inventory.reloading > 0.0 || inventory.clips[inventory.current] >= weapon.clip || inventory.ammo[ammo_type] == 0 {return None;}
+    game.inventory.reloading = weapon.reload_time.max(f32::MIN_POSITIVE);
+    Some(GameEvent::Reloading(game.inventory.current))
+}
+
+/// Moves rounds from the carried ammo to the clip of the held weapon, up to its size
+fn finish_reload(game: &mut Game){
+    let inventory = &mut game.inventory;
+    let weapon = &game.arsenal.weapons[inventory.current];
+    let Some(ammo_type) = weapon.ammo else {return;};
+    let rounds = (weapon.clip - inventory.clips[inventory.current]).min(inventory.ammo[ammo_type]);
+    inventory.clips[inventory.current] += rounds;
+    inventory.ammo[ammo_type] -= rounds;
+}
+
+/// Runs the weapons for a tick of delta_time seconds: switches and reloads from the input and fires while fire is held,
+/// at most at the fire rate of the held weapon
+pub fn update_weapons(game: &mut Game, input: &Input, delta_time: f32) -> Vec<GameEvent>{
+    let mut events: Vec<GameEvent> = Vec::new();
+    game.inventory.cooldown = (game.inventory.cooldown - delta_time).max(0.0);
+    game.inventory.since_shot += delta_time;
+    game.inventory.switching = (game.inventory.switching - delta_time).max(0.0);
+    if game.inventory.reloading > 0.0{
+        game.inventory.reloading = (game.inventory.reloading - delta_time).max(0.0);
+        if game.inventory.reloading == 0.0 {finish_reload(game);}
+    }
+    let weapon_count = game.arsenal.weapons.len() as i32;
+    let selected = input.select.or((input.cycle != 0).then(|| (game.inventory.current as i32 + input.cycle).rem_euclid(weapon_count) as usize));
+    events.extend(selected.and_then(|idx| switch_weapon(game, idx)));
+    if input.reload {events.extend(reload(game));}
+    if !input.fire || game.inventory.cooldown > 0.0 || game.inventory.reloading > 0.0 || game.inventory.switching > 0.0 {return events;}
+    let weapon = game.arsenal.weapons[game.inventory.current].clone();
+    game.inventory.cooldown = 1.0 / weapon.fire_rate;
+    if !game.inventory.loaded(&weapon){
+        // An empty clip is reloaded, without ammo the weapon only clicks
+        events.push(reload(game).unwrap_or(GameEvent::OutOfAmmo));
+        return events;
+    }
+    match weapon.ammo{
+        Some(_) if weapon.clip > 0 => game.inventory.clips[game.inventory.current] -= 1,
+        Some(ammo_type) => game.inventory.ammo[ammo_type] -= 1,
+        None => {},
+    }
+    game.inventory.since_shot = 0.0;
+    game.player.fired = true;
+    events.push(GameEvent::Fired(game.inventory.current));
+    for _ in 0..weapon.pellets{
+        let angle = game.player.angle + weapon.spread * game.inventory.random();
+        if let Some(hit) = fire(game, angle){
+            events.push(GameEvent::Hit(hit));
+            if let hitscan::Target::Entity(idx) = hit.target{
+                events.extend(entity::damage(game, idx, weapon.damage));
+            }
+        }
+    }
+    // The last round of the clip starts the reload
+    if weapon.clip > 0 && game.inventory.clips[game.inventory.current] == 0 {events.extend(reload(game));}
+    events
+}
+
+/// Reads a weapons file, errors start with the path of the file
+pub fn load_weapons(path: &str) -> Result<Arsenal, String>{
+    let source = fs::read_to_string(path).map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+    parse_weapons(&source).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Parses the text of a weapons file, see assets/weapons.cfg for the format. Errors about a single line start with its number
+pub fn parse_weapons(source: &str) -> Result<Arsenal, String>{
+    let mut arsenal = Arsenal{ weapons: Vec::new(), ammo_types: Vec::new() };
+    // Keywords given for every weapon, so none is given twice or missing
+    let mut given: Vec<Vec<&str>> = Vec::new();
+    for (line_idx, line) in source.lines().enumerate(){
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {continue;}
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let error = |message: String| format!("line {}: {}", line_idx + 1, message);
+        let frames = |usage: &str| words[1..].iter().map(|word| word.parse::<usize>().ok()).collect::<Option<Vec<usize>>>()
+            .filter(|frames| !frames.is_empty()).ok_or_else(|| error(format!("expected '{}'", usage)));
+        let number = |usage: &str| single::<f32>(&words).filter(|value| *value >= 0.0).ok_or_else(|| error(format!("expected '{}'", usage)));
+        if words[0] == "ammo_type"{
+            let usage = || error("expected 'ammo_type <name> <most carried> <carried after spawning>'".to_string());
+            let [max, start] = match words[..]{
+                [_, _, max, start] => [max, start].map(|word| word.parse::<u32>().ok()),
+                _ => return Err(usage()),
+            };
+            let (Some(max), Some(start)) = (max, start) else {return Err(usage());};
+            if arsenal.ammo_types.iter().any(|ammo_type| ammo_type.name == words[1]) {return Err(error(format!("ammo type '{}' is given more than once", words[1])));}
+            arsenal.ammo_types.push(AmmoType{ name: words[1].to_string(), max, start: start.min(max) });
+            continue;
+        }
+        if words[0] == "weapon"{
+            if words.len() != 2 {return Err(error("expected 'weapon <name>'".to_string()));}
+            if arsenal.weapons.iter().any(|weapon| weapon.name == words[1]) {return Err(error(format!("weapon '{}' is given more than once", words[1])));}
+            arsenal.weapons.push(Weapon{
+                name: words[1].to_string(),
+                fire_rate: 0.0,
+                damage: 0,
+                pellets: 1,
+                spread: 0.0,
+                ammo: None,
+                clip: 0,
+                reload_time: 0.0,
+                sheet: String::new(),
+                frame_size: (0, 0),
+                idle_frame: 0,
+                fire_frames: Vec::new(),
+                reload_frames: Vec::new(),
+                sound: String::new(),
+            });
+            given.push(Vec::new());
+            continue;
+        }
+        let (Some(weapon), Some(weapon_lines)) = (arsenal.weapons.last_mut(), given.last_mut()) else {
+            return Err(error(format!("unknown keyword '{}', weapons start with 'weapon <name>'", words[0])));
+        };
+        if weapon_lines.contains(&words[0]) {return Err(error(format!("{} of weapon '{}' is given more than once", words[0], weapon.name)));}
+        match words[0]{
+            "fire_rate" => {
+                weapon.fire_rate = number("fire_rate <shots per second>")?;
+                if weapon.fire_rate == 0.0 {return Err(error("fire_rate can't be 0".to_string()));}
+            },
+            "damage" => weapon.damage = single(&words).filter(|damage| *damage >= 0).ok_or_else(|| error("expected 'damage <health>'".to_string()))?,
+            "pellets" => {
+                weapon.pellets = single(&words).ok_or_else(|| error("expected 'pellets <shots at once>'".to_string()))?;
+                if weapon.pellets == 0 {return Err(error("pellets can't be 0".to_string()));}
+            },
+            "spread" => weapon.spread = number("spread <degrees>")?,
+            "clip" => weapon.clip = single(&words).ok_or_else(|| error("expected 'clip <rounds>'".to_string()))?,
+            "reload" => weapon.reload_time = number("reload <seconds>")?,
+            "ammo" => {
+                if words.len() != 2 {return Err(error("expected 'ammo <ammo type>'".to_string()));}
+                let ammo_type = arsenal.ammo_types.iter().position(|ammo_type| ammo_type.name == words[1])
+                    .ok_or_else(|| error(format!("unknown ammo type '{}', ammo types are given before the weapons using them", words[1])))?;
+                weapon.ammo = Some(ammo_type);
+            },
+            "sheet" => {
+                let usage = || error("expected 'sheet <path> <frame width> <frame height>'".to_string());
+                let [width, height] = match words[..]{
+                    [_, _, width, height] => [width, height].map(|word| word.parse::<u32>().ok().filter(|size| *size > 0)),
+                    _ => return Err(usage()),
+                };
+                let (Some(width), Some(height)) = (width, height) else {return Err(usage());};
+                weapon.sheet = words[1].to_string();
+                weapon.frame_size = (width, height);
+            },
+            "idle_frame" => weapon.idle_frame = single(&words).ok_or_else(|| error("expected 'idle_frame <frame>'".to_string()))?,
+            "fire_frames" => weapon.fire_frames = frames("fire_frames <frame>...")?,
+            "reload_frames" => weapon.reload_frames = frames("reload_frames <frame>...")?,
+            "sound" => match words[1..]{
+                [path] => weapon.sound = path.to_string(),
+                _ => return Err(error("expected 'sound <path>'".to_string())),
+            },
+            other => return Err(error(format!("unknown keyword '{}'", other))),
+        }
+        weapon_lines.push(words[0]);
+    }
+    if arsenal.weapons.is_empty() {return Err("the weapons file needs at least one weapon".to_string());}
+    for (weapon, given) in arsenal.weapons.iter().zip(&given){
+        for required in ["fire_rate", "damage", "sheet", "sound"]{
+            if !given.contains(&required) {return Err(format!("{} of weapon '{}' is missing", required, weapon.name));}
+        }
+    }
+    Ok(arsenal)
+}
+
+/// Returns the only word after the keyword of a line read as a T, None if there are more words or it isn't one
+fn single<T: FromStr>(words: &[&str]) -> Option<T>{
+    match words{
+        [_, word] => word.parse().ok(),
+        _ => None,
+    }
+}
+
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    /// A pistol with a clip of 2, a shotgun without a clip and a knife that never runs out
+    const SMALL_WEAPONS: &str = "ammo_type bullets 10 3
+ammo_type shells 20 1
+weapon pistol
+fire_rate 2
+damage 25
+ammo bullets
+clip 2
+reload 0.5
+sheet pistol.png 128 184
+fire_frames 1
+reload_frames 2 3
+sound shot.wav
+weapon shotgun
+fire_rate 1
+damage 10
+pellets 5
+spread 10
+ammo shells
+sheet shotgun.png 128 184
+sound shotgun.wav
+weapon knife
+fire_rate 4
+damage 50
+sheet knife.png 64 64
+idle_frame 3
+fire_frames 0 1 2
+sound slash.wav
+";
+
+    fn test_game() -> Game{
+        let mut game = Game::new(Player::new(300.0, 300.0, 0.0), map::load_map(map::DEFAULT_MAP).unwrap().game_map);
+        game.arm(parse_weapons(SMALL_WEAPONS).unwrap());
+        game
+    }
+
+    /// Returns the events of holding the input for the seconds
+    fn hold(game: &mut Game, input: &Input, seconds: f32) -> Vec<GameEvent>{
+        (0..(seconds * TICK_RATE as f32).round() as usize).flat_map(|_| update_weapons(game, input, TICK_TIME)).collect()
+    }
+
+    #[test]
+    fn test_parse_weapons() {
+        let arsenal = parse_weapons(SMALL_WEAPONS).unwrap();
+        assert_eq!(arsenal.ammo_types[1], AmmoType{ name: "shells".to_string(), max: 20, start: 1 });
+        assert_eq!(arsenal.weapons.len(), 3);
+        let shotgun = &arsenal.weapons[1];
+        assert_eq!((shotgun.pellets, shotgun.spread, shotgun.ammo, shotgun.clip), (5, 10.0, Some(1), 0));
+        assert_eq!(arsenal.weapons[2].ammo, None);
+        let knife = &arsenal.weapons[2];
+        assert_eq!((knife.frame_size, knife.idle_frame, &knife.fire_frames), ((64, 64), 3, &vec![0, 1, 2]));
+        assert!(shotgun.reload_frames.is_empty());
+        let inventory = Inventory::new(&arsenal);
+        assert_eq!((inventory.clips, inventory.ammo), (vec![2, 0, 0], vec![3, 1]));
+    }
+
+    #[test]
+    fn test_parse_weapons_errors() {
+        let broken = [
+            ("ammo_type bullets 10 3", "ammo_type bullets 10", "line 1: expected 'ammo_type <name> <most carried> <carried after spawning>'"),
+            ("ammo_type shells 20 1", "ammo_type bullets 20 1", "line 2: ammo type 'bullets' is given more than once"),
+            ("ammo_type bullets 10 3\n", "", "line 5: unknown ammo type 'bullets', ammo types are given before the weapons using them"),
+            ("weapon knife", "weapon pistol", "line 21: weapon 'pistol' is given more than once"),
+            ("fire_rate 2", "fire_rate fast", "line 4: expected 'fire_rate <shots per second>'"),
+            ("fire_rate 2", "fire_rate 0", "line 4: fire_rate can't be 0"),
+            ("damage 25", "damage 25\ndamage 30", "line 6: damage of weapon 'pistol' is given more than once"),
+            ("pellets 5", "pellets -5", "line 16: expected 'pellets <shots at once>'"),
+            ("damage 25", "damage 2.5", "line 5: expected 'damage <health>'"),
+            ("damage 25", "damage -25", "line 5: expected 'damage <health>'"),
+            ("clip 2", "clip 7.9", "line 7: expected 'clip <rounds>'"),
+            ("idle_frame 3", "idle_frame 1e30", "line 25: expected 'idle_frame <frame>'"),
+            ("sound shot.wav", "sound", "line 12: expected 'sound <path>'"),
+            ("sound slash.wav\n", "", "sound of weapon 'knife' is missing"),
+            ("clip 2", "magazine 2", "line 7: unknown keyword 'magazine'"),
+            ("sheet pistol.png 128 184", "sheet pistol.png 128", "line 9: expected 'sheet <path> <frame width> <frame height>'"),
+            ("sheet knife.png 64 64", "sheet knife.png 0 64", "line 24: expected 'sheet <path> <frame width> <frame height>'"),
+            ("fire_frames 0 1 2", "fire_frames 0 one", "line 26: expected 'fire_frames <frame>...'"),
+            ("sheet shotgun.png 128 184\n", "", "sheet of weapon 'shotgun' is missing"),
+        ];
+        for (from, to, expected) in broken{
+            assert_eq!(parse_weapons(&SMALL_WEAPONS.replacen(from, to, 1)).unwrap_err(), expected);
+        }
+        assert_eq!(parse_weapons("damage 5").unwrap_err(), "line 1: unknown keyword 'damage', weapons start with 'weapon <name>'");
+        assert_eq!(parse_weapons("ammo_type bullets 10 3").unwrap_err(), "the weapons file needs at least one weapon");
+    }
+
+    #[test]
+    fn test_default_weapons() {
+        let arsenal = load_weapons(DEFAULT_WEAPONS).unwrap();
+        assert!(arsenal.weapons.len() > 1);
+        assert!(arsenal.weapons.iter().all(|weapon| std::path::Path::new(&weapon.sound).exists()));
+        // Every frame fits the sheet
+        for weapon in arsenal.weapons.iter().chain(&Arsenal::default().weapons){
+            view_model::load_sheet(weapon).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_fire_rate_and_reload() {
+        let mut game = test_game();
+        let fire = Input{ fire: true, ..Input::default() };
+        // Holding fire shoots 2 times a second, the clip is empty after 2 shots and reloaded
+        let events = hold(&mut game, &fire, 0.6);
+        assert_eq!(events.iter().filter(|event| **event == GameEvent::Fired(0)).count(), 2);
+        assert_eq!(events.last(), Some(&GameEvent::Reloading(0)));
+        assert_eq!(game.inventory.clips[0], 0);
+        hold(&mut game, &Input::default(), 0.6);
+        assert_eq!((game.inventory.clips[0], game.inventory.ammo[0]), (2, 1));
+        // Reloading by hand tops the clip up with what is left
+        update_weapons(&mut game, &fire, TICK_TIME);
+        assert_eq!(update_weapons(&mut game, &Input{ reload: true, ..Input::default() }, TICK_TIME), vec![GameEvent::Reloading(0)]);
+        hold(&mut game, &Input::default(), 0.6);
+        assert_eq!((game.inventory.clips[0], game.inventory.ammo[0]), (2, 0));
+        // Without ammo the weapon only clicks
+        hold(&mut game, &fire, 1.2);
+        assert_eq!(hold(&mut game, &fire, 0.5), vec![GameEvent::OutOfAmmo]);
+    }
+
+    #[test]
+    fn test_switch_weapon() {
+        let mut game = test_game();
+        let fire = Input{ fire: true, ..Input::default() };
+        hold(&mut game, &fire, 0.6);
+        // Switching cancels the reload, the number keys select and the wheel goes around
+        assert_eq!(update_weapons(&mut game, &Input{ select: Some(2), ..Input::default() }, TICK_TIME), vec![GameEvent::Switched(2)]);
+        assert_eq!(game.inventory.reloading, 0.0);
+        assert!(update_weapons(&mut game, &Input{ select: Some(5), ..Input::default() }, TICK_TIME).is_empty());
+        update_weapons(&mut game, &Input{ cycle: 1, ..Input::default() }, TICK_TIME);
+        assert_eq!(game.inventory.current, 0);
+        update_weapons(&mut game, &Input{ cycle: -1, ..Input::default() }, TICK_TIME);
+        assert_eq!(game.inventory.current, 2);
+        assert_eq!(game.inventory.previous, 0);
+        // Nothing fires until the knife is raised, then knives never run out
+        assert!(hold(&mut game, &fire, SWITCH_TIME - 0.05).is_empty());
+        hold(&mut game, &Input::default(), 0.5);
+        let events = hold(&mut game, &fire, 2.0);
+        assert_eq!(events.iter().filter(|event| **event == GameEvent::Fired(2)).count(), 8);
+        assert!(!events.contains(&GameEvent::OutOfAmmo));
+    }
+
+    #[test]
+    fn test_spread() {
+        let mut game = test_game();
+        game.entities = vec![entity::Entity::new(entity::Behaviour::Enemy, 500.0, 300.0, 0.0, 0)];
+        update_weapons(&mut game, &Input{ select: Some(1), ..Input::default() }, TICK_TIME);
+        hold(&mut game, &Input::default(), SWITCH_TIME);
+        let events = update_weapons(&mut game, &Input{ fire: true, ..Input::default() }, TICK_TIME);
+        // Every pellet is a shot, they spread to both sides the same way every game
+        let hits: Vec<hitscan::Hit> = events.iter().filter_map(|event| match event {
+            GameEvent::Hit(hit) => Some(*hit),
+            _ => None,
+        }).collect();
+        assert_eq!(hits.len(), 5);
+        assert!(hits.iter().any(|hit| hit.pos_y < 295.0) && hits.iter().any(|hit| hit.pos_y > 305.0));
+        assert!(hits.iter().any(|hit| hit.target == hitscan::Target::Entity(0)));
+        let mut again = test_game();
+        again.entities = vec![entity::Entity::new(entity::Behaviour::Enemy, 500.0, 300.0, 0.0, 0)];
+        update_weapons(&mut again, &Input{ select: Some(1), ..Input::default() }, TICK_TIME);
+        hold(&mut again, &Input::default(), SWITCH_TIME);
+        assert_eq!(update_weapons(&mut again, &Input{ fire: true, ..Input::default() }, TICK_TIME), events);
+        // The shotgun has no clip, it fires from the carried shells
+        assert_eq!(game.inventory.ammo[1], 0);
+    }
+}
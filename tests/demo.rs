@@ -0,0 +1,46 @@
+//! Replays the recorded demos of tests/demos, the game has to end up the same every time
+use ya_raycaster::*;
+use ya_raycaster::demo::{load_demo, play};
+use ya_raycaster::hitscan::Target;
+
+fn asset(path: &str) -> String{
+    format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path)
+}
+
+#[test]
+fn test_walk_and_shoot() {
+    let demo = load_demo(&asset("tests/demos/walk_and_shoot.demo")).expect("Couldn't load the demo");
+    let map_file = map::load_map(&asset(&demo.map)).expect("Couldn't load the map");
+    // The demo was recorded before there were weapons files, with the built-in gun
+    let arsenal = match &demo.weapons {
+        Some(path) => weapon::load_weapons(&asset(path)).expect("Couldn't load the weapons"),
+        None => weapon::Arsenal::default(),
+    };
+    let mut game = map_file.new_game();
+    game.arm(arsenal.clone());
+    let events = play(&mut game, &demo);
+    let mut replayed = map_file.new_game();
+    replayed.arm(arsenal);
+    assert_eq!(play(&mut replayed, &demo), events);
+    assert_eq!(replayed.player, game.player);
+    // The first shots hit a wall, the last one the enemy that came after the player
+    let hits: Vec<Target> = events.iter().filter_map(|event| match event {
+        GameEvent::Hit(hit) => Some(hit.target),
+        _ => None,
+    }).collect();
+    assert_eq!(events.iter().filter(|event| **event == GameEvent::Fired(0)).count(), 3);
+    assert_eq!(hits.len(), 3);
+    assert_eq!(hits[2], Target::Entity(4));
+    assert_eq!(game.entities[4].health.map(|health| health.health), Some(entity::ENEMY_HEALTH - hitscan::SHOT_DAMAGE));
+    assert_eq!(game.decals.len(), 2);
+    // Its shots hurt the player every time
+    let shots = events.iter().filter(|event| matches!(event, GameEvent::EnemyFired(_))).count();
+    assert!(shots > 0);
+    assert_eq!(game.player.health.health, damage::PLAYER_HEALTH - shots as i32 * ai::ENEMY_SHOT_DAMAGE);
+    assert_eq!(game.player.state, PlayerState::Alive);
+    // The jump landed and the player stopped in the north of the map
+    let block = BLOCKSIZE as f32;
+    assert_eq!(((game.player.pos_x / block) as i32, (game.player.pos_y / block) as i32), (8, 2));
+    assert_eq!(game.player.pos_z, 0.0);
+    assert!((game.player.angle - 345.0).abs() < 0.01);
+}
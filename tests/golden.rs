@@ -0,0 +1,109 @@
+//! Golden image tests, renders frames from scripted positions and compares them with tests/golden/*.png
+//! Run with UPDATE_GOLDEN=1 to (re)create the reference images after an intended change to the picture
+use std::fs;
+use std::path::PathBuf;
+use sdl2::pixels::Color;
+use ya_raycaster::*;
+use ya_raycaster::framebuffer::FrameBuffer;
+use ya_raycaster::texture::TextureSet;
+
+/// Channel difference up to this is still counted as the same color
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Ratio of pixels that are allowed to differ more than CHANNEL_TOLERANCE
+const MAX_DIFFERENT_PIXELS: f32 = 0.001;
+
+struct Shot{
+    name: &'static str,
+    pos_x: f32,
+    pos_y: f32,
+    pos_z: f32, // height the player stands at
+    angle: f32,
+    pitch: f32,
+}
+
+const SHOTS: [Shot; 6] = [
+    Shot{ name: "spawn", pos_x: 300.0, pos_y: 300.0, pos_z: 0.0, angle: 60.0, pitch: 0.0 },
+    Shot{ name: "facing_tower", pos_x: 500.0, pos_y: 400.0, pos_z: 0.0, angle: 300.0, pitch: 0.0 },
+    Shot{ name: "corridor", pos_x: 100.0, pos_y: 500.0, pos_z: 0.0, angle: 270.0, pitch: 0.0 },
+    Shot{ name: "close_to_wall", pos_x: 420.0, pos_y: 900.0, pos_z: 0.0, angle: 225.0, pitch: 0.0 },
+    Shot{ name: "on_outer_wall", pos_x: 32.0, pos_y: 300.0, pos_z: 64.0, angle: 345.0, pitch: 0.0 },
+    Shot{ name: "looking_up_tower", pos_x: 500.0, pos_y: 400.0, pos_z: 0.0, angle: 300.0, pitch: 25.0 },
+];
+
+fn asset(path: &str) -> String{
+    format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path)
+}
+
+/// Renders the frame the player would see on the screen
+fn render_shot(shot: &Shot) -> FrameBuffer{
+    let map_file = map::load_map(&asset(map::DEFAULT_MAP)).expect("Couldn't load the map");
+    let mut game = map_file.new_game();
+    game.arm(weapon::load_weapons(&asset(weapon::DEFAULT_WEAPONS)).expect("Couldn't load the weapons"));
+    game.player = Player::new(shot.pos_x, shot.pos_y, shot.angle);
+    game.player.pos_z = shot.pos_z;
+    game.player.pitch = shot.pitch;
+    // Tests are run from the package directory, so the relative texture paths of the map work
+    let textures = TextureSet::load(&map_file.palette).expect("Couldn't load texture");
+    let load = |path: &str| FrameBuffer::load_png(&asset(path)).expect("Couldn't load texture");
+    let weapon_sheets: Vec<FrameBuffer> = game.arsenal.weapons.iter().map(|weapon| load(&weapon.sheet)).collect();
+    let mut frame = FrameBuffer::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+    let mut render_state = RenderState::new(&game);
+    render(&mut frame, &mut render_state, &game, &Snapshot::of(&game), 1.0, &textures, &weapon_sheets);
+    frame
+}
+
+/// Compares two frames, returns the number of different pixels and an image marking them in red
+fn compare(expected: &FrameBuffer, actual: &FrameBuffer) -> (usize, FrameBuffer){
+    let mut diff = FrameBuffer::new(actual.width, actual.height);
+    let mut different_pixels = 0;
+    for y in 0..actual.height{
+        for x in 0..actual.width{
+            let (a, b) = (expected.get_pixel(x, y), actual.get_pixel(x, y));
+            let same = [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)].iter()
+                .all(|(a, b)| a.abs_diff(*b) <= CHANNEL_TOLERANCE);
+            if same{
+                // Faded expected picture so the differences stand out
+                let gray = ((a.r as u32 + a.g as u32 + a.b as u32) / 9) as u8;
+                diff.set_pixel(x as i32, y as i32, Color::RGB(gray, gray, gray));
+            }
+            else{
+                different_pixels += 1;
+                diff.set_pixel(x as i32, y as i32, RED);
+            }
+        }
+    }
+    (different_pixels, diff)
+}
+
+#[test]
+fn test_golden_frames() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok();
+    let output_dir = PathBuf::from(asset("target/golden"));
+    let mut failures: Vec<String> = Vec::new();
+    for shot in SHOTS.iter(){
+        let actual = render_shot(shot);
+        let reference = asset(&format!("tests/golden/{}.png", shot.name));
+        if update{
+            fs::create_dir_all(asset("tests/golden")).expect("Couldn't create the reference directory");
+            actual.save_png(&reference).expect("Couldn't save the reference image");
+            continue;
+        }
+        let expected = FrameBuffer::load_png(&reference)
+            .unwrap_or_else(|e| panic!("{}, run with UPDATE_GOLDEN=1 to create it", e));
+        if (expected.width, expected.height) != (actual.width, actual.height){
+            failures.push(format!("{}: size is {}x{}, expected {}x{}", shot.name, actual.width, actual.height, expected.width, expected.height));
+            continue;
+        }
+        let (different_pixels, diff) = compare(&expected, &actual);
+        let allowed = (MAX_DIFFERENT_PIXELS * (actual.width * actual.height) as f32) as usize;
+        if different_pixels > allowed{
+            fs::create_dir_all(&output_dir).expect("Couldn't create the output directory");
+            let actual_path = output_dir.join(format!("{}_actual.png", shot.name));
+            let diff_path = output_dir.join(format!("{}_diff.png", shot.name));
+            actual.save_png(actual_path.to_str().unwrap()).expect("Couldn't save the frame");
+            diff.save_png(diff_path.to_str().unwrap()).expect("Couldn't save the diff");
+            failures.push(format!("{}: {} pixels differ (allowed {}), see {}", shot.name, different_pixels, allowed, diff_path.display()));
+        }
+    }
+    assert!(failures.is_empty(), "Frames don't match the references:\n{}", failures.join("\n"));
+}